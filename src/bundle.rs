@@ -0,0 +1,41 @@
+use crate::exercise::ManifestFormat;
+use flate2::read::GzDecoder;
+use std::error::Error;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+// Extract a bundle of exercises (a .tar.gz, .tgz or .zip archive) into `dir`.
+// Returns the directory holding the bundle's manifest: `dir` itself, or the
+// single top-level directory the archive was made from.
+pub fn extract(bundle: &Path, dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let file =
+        File::open(bundle).map_err(|e| format!("Failed to open {}: {e}", bundle.display()))?;
+    let name = bundle.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        zip::ZipArchive::new(file)?.extract(dir)?;
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        tar::Archive::new(GzDecoder::new(file)).unpack(dir)?;
+    } else {
+        return Err(format!("{} isn't a .tar.gz, .tgz or .zip archive", bundle.display()).into());
+    }
+
+    if has_manifest(dir) {
+        return Ok(dir.to_path_buf());
+    }
+    let entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    match entries.as_slice() {
+        [entry] if has_manifest(&entry.path()) => Ok(entry.path()),
+        _ => Err(format!(
+            "{} doesn't contain an info.toml or info.yaml",
+            bundle.display()
+        )
+        .into()),
+    }
+}
+
+// Whether `dir` holds a top-level manifest of any format
+fn has_manifest(dir: &Path) -> bool {
+    [ManifestFormat::Toml, ManifestFormat::Yaml]
+        .iter()
+        .any(|format| dir.join(format.info_path()).exists())
+}
@@ -1,6 +1,10 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Clone, Debug)]
 pub struct Record {
@@ -11,12 +15,12 @@ pub struct Record {
 }
 
 impl Record {
-    pub fn empty() -> Self {
+    pub fn new(code_path: &Path) -> Self {
         Record {
             wrong_codes: Vec::new(),
             errors: Vec::new(),
             right_code: String::new(),
-            code_path: PathBuf::default(),
+            code_path: code_path.to_path_buf(),
         }
     }
 
@@ -24,28 +28,18 @@ impl Record {
         assert_eq!(self.errors.len(), self.wrong_codes.len());
         assert!(!self.right_code.is_empty());
         let mut out = String::new();
-        // let num = self.errors.len();
-
         let it = self.wrong_codes.iter().zip(self.errors.iter());
-        // let v: Vec<(&String, &String)> = it.collect();
-        // return Some(format_args!("{:?}", v).to_string().to_owned());
         for (wrong_code, error) in it {
             if error.is_empty() {
                 continue;
             }
-            out.push_str(
-                format!(
-                    "{{\"wrong_code\": \"{}\", \"error\": \"{}\", \"right_code\": \"{}\"}}\n",
-                    wrong_code.replace("\n", "\\n").replace("\t", "\\n").replace("\"", "\\\""),
-                    error.replace("\n", "\\n").replace("\t", "\\n").replace("\"", "\\\""),
-                    self.right_code.replace("\n", "\\n").replace("\t", "\\n").replace("\"", "\\\"")
-                )
-                .as_str(),
-            );
-
-            // if i < num - 1 {
-            //     out.push_str(",\n");
-            // }
+            let line = serde_json::json!({
+                "wrong_code": wrong_code,
+                "error": error,
+                "right_code": self.right_code,
+                "path": self.code_path.display().to_string(),
+            });
+            out.push_str(&format!("{line}\n"));
         }
 
         if out.is_empty() {
@@ -59,66 +53,401 @@ impl Record {
         self.errors.is_empty()
     }
 
-    pub fn check_file(&self, path: &PathBuf) -> bool {
-        self.code_path.file_name() == path.file_name()
-    }
-
     fn read_wrong_code(&mut self) {
         let code = fs::read(&self.code_path).unwrap();
-        self.wrong_codes.push(String::from_utf8(code).unwrap());
+        self.wrong_codes
+            .push(String::from_utf8_lossy(&code).into_owned());
     }
 
     pub fn read_right_code(&mut self) {
         let code = fs::read(&self.code_path).unwrap();
-        self.right_code = String::from_utf8(code).unwrap();
+        self.right_code = String::from_utf8_lossy(&code).into_owned();
     }
 
-    pub fn set_error(&mut self, error: &String) {
-        if !self.errors.iter().any(|previous| previous.eq(error)) {
-            self.errors.push(error.clone());
+    pub fn set_error(&mut self, error: &str) {
+        if !self.errors.iter().any(|previous| previous == error) {
+            self.errors.push(error.to_string());
             self.read_wrong_code();
         }
     }
+}
 
-    pub fn reset_path(&mut self, code_path: &PathBuf) {
-        if !self.code_path.file_name().eq(&code_path.file_name()) {
-            self.clear();
-        }
-        self.code_path = code_path.clone();
-    }
+// The recorded effort spent on an exercise
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize)]
+pub struct Effort {
+    // The failed attempts at it
+    pub attempts: usize,
+    // Seconds between the first and the last record with a timestamp
+    pub seconds: u64,
+}
 
-    pub fn clear(&mut self) {
-        self.code_path = PathBuf::default();
-        self.right_code.clear();
-        self.wrong_codes.clear();
-        self.errors.clear();
-    }
+// A stable 64-bit FNV-1a hash of the file content, to tell edits apart.
+// Unreadable files hash like empty ones.
+pub fn content_hash(path: &Path) -> u64 {
+    fnv_hash(&fs::read(path).unwrap_or_default())
+}
+
+// A stable 64-bit FNV-1a hash of the bytes
+pub fn fnv_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
 }
 
+// Seconds since the Unix epoch, the time of the records in the dump file
+// and of the watch history entries
+pub fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+// Collects the failed attempts at each exercise and appends them to the
+// dump file once the exercise passes. Safe to share between threads
+// verifying different exercises.
 pub struct DataGather {
     dump_path: PathBuf,
+    // Also append a line for every failed attempt as it happens
+    attempts: bool,
+    // The attempts at the exercises that haven't passed yet
+    pending: Mutex<HashMap<PathBuf, Record>>,
+    // Serializes the appends to the dump file
+    lock: Mutex<()>,
 }
 
 impl DataGather {
     pub fn new(path: PathBuf) -> Self {
         DataGather {
-            dump_path: path.clone(),
+            dump_path: path,
+            attempts: false,
+            pending: Mutex::new(HashMap::new()),
+            lock: Mutex::new(()),
         }
     }
 
-    pub fn push(&self, record: Record) {
+    // Record every failed attempt, not only the distinct errors of
+    // exercises that end up passing
+    pub fn with_attempts(mut self, attempts: bool) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    // Remember a failed attempt at the exercise at `path`
+    pub fn failed(&self, path: &Path, error: &str) {
+        if self.attempts {
+            self.push_attempt(path, error);
+        }
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(path.to_path_buf())
+            .or_insert_with(|| Record::new(path))
+            .set_error(error);
+    }
+
+    // Write the failed attempts at the exercise at `path` along with its solution
+    pub fn passed(&self, path: &Path) {
+        let record = self.pending.lock().unwrap().remove(path);
+        if let Some(mut record) = record {
+            record.read_right_code();
+            self.push(&record);
+        }
+    }
+
+    pub fn push(&self, record: &Record) {
+        if record.is_empty() {
+            return;
+        }
+        if let Some(data) = record.to_json() {
+            let _guard = self.lock.lock().unwrap();
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.dump_path)
+                .unwrap();
+            file.write_all(data.as_bytes()).unwrap();
+        }
+    }
+
+    // Append a line for a single failed attempt
+    fn push_attempt(&self, path: &Path, error: &str) {
+        let timestamp = timestamp();
+        let line = serde_json::json!({
+            "attempt": true,
+            "path": path.display().to_string(),
+            "error": error,
+            "content_hash": format!("{:016x}", content_hash(path)),
+            "timestamp": timestamp,
+        });
+        let _guard = self.lock.lock().unwrap();
         let mut file = fs::OpenOptions::new()
             .create(true)
             .append(true)
-            .write(true)
             .open(&self.dump_path)
             .unwrap();
+        file.write_all(format!("{line}\n").as_bytes()).unwrap();
+    }
+
+    // Record that the hint of the exercise at `path` was shown
+    pub fn hint_viewed(&self, path: &Path) -> io::Result<()> {
+        let timestamp = timestamp();
+        let line = serde_json::json!({
+            "hint_viewed": true,
+            "path": path.display().to_string(),
+            "timestamp": timestamp,
+        });
+        let _guard = self.lock.lock().unwrap();
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.dump_path)?;
+        file.write_all(format!("{line}\n").as_bytes())
+    }
 
-        if !record.is_empty() {
-            if let Some(data) = record.to_json() {
-                file.write(data.as_bytes()).unwrap();
-                file.write(b"\n").unwrap();
+    // How many times the hint of each exercise was shown, by exercise path
+    pub fn hint_views(&self) -> io::Result<HashMap<String, usize>> {
+        let mut views = HashMap::new();
+        for record in self.records()? {
+            if record["hint_viewed"] == true {
+                if let Some(path) = record["path"].as_str() {
+                    *views.entry(path.to_string()).or_insert(0) += 1;
+                }
+            }
+        }
+        Ok(views)
+    }
+
+    // The effort spent on each exercise, by exercise path. The failed
+    // attempts are counted from the attempt lines when they were recorded
+    // and from the distinct errors kept with the solution otherwise.
+    pub fn efforts(&self) -> io::Result<HashMap<String, Effort>> {
+        #[derive(Default)]
+        struct Tally {
+            attempts: usize,
+            errors: usize,
+            first: Option<u64>,
+            last: Option<u64>,
+        }
+        let mut tallies: HashMap<String, Tally> = HashMap::new();
+        for record in self.records()? {
+            let Some(path) = record["path"].as_str() else {
+                continue;
+            };
+            let tally = tallies.entry(path.to_string()).or_default();
+            if record["attempt"] == true {
+                tally.attempts += 1;
+            } else if record["error"].as_str().is_some_and(|e| !e.is_empty()) {
+                tally.errors += 1;
+            }
+            if let Some(timestamp) = record["timestamp"].as_u64() {
+                tally.first = Some(tally.first.map_or(timestamp, |first| first.min(timestamp)));
+                tally.last = Some(tally.last.map_or(timestamp, |last| last.max(timestamp)));
             }
         }
+        Ok(tallies
+            .into_iter()
+            .map(|(path, tally)| {
+                let effort = Effort {
+                    attempts: if tally.attempts > 0 {
+                        tally.attempts
+                    } else {
+                        tally.errors
+                    },
+                    seconds: tally.last.zip(tally.first).map_or(0, |(l, f)| l - f),
+                };
+                (path, effort)
+            })
+            .collect())
+    }
+
+    // Append a record holding only the solution of each solved exercise
+    // that has no record yet, for solves made before data was gathered.
+    // Returns how many records were written.
+    pub fn backfill(&self, paths: &[&Path]) -> io::Result<usize> {
+        let _guard = self.lock.lock().unwrap();
+        let recorded = self.recorded_paths()?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.dump_path)?;
+        let mut written = 0;
+        for path in paths {
+            let path_str = path.display().to_string();
+            if recorded.contains(&path_str) {
+                continue;
+            }
+            let record = serde_json::json!({
+                "wrong_code": "",
+                "error": "",
+                "right_code": fs::read_to_string(path)?,
+                "path": path_str,
+            });
+            file.write_all(format!("{record}\n").as_bytes())?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    // The exercise paths that already have records
+    fn recorded_paths(&self) -> io::Result<HashSet<String>> {
+        Ok(self
+            .records()?
+            .into_iter()
+            // Attempt and hint lines hold no solution
+            .filter(|record| record.get("right_code").is_some())
+            .filter_map(|record| record["path"].as_str().map(String::from))
+            .collect())
+    }
+
+    // Every parseable line of the dump file, none if it doesn't exist yet
+    fn records(&self) -> io::Result<Vec<serde_json::Value>> {
+        let data = match fs::read_to_string(&self.dump_path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(data
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_push_from_threads() {
+        let dir = std::env::temp_dir().join(format!("rustlings_data_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data_gather = Arc::new(DataGather::new(dir.join("data.jsonl")));
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let data_gather = Arc::clone(&data_gather);
+                let path = dir.join(format!("exercise{i}.rs"));
+                thread::spawn(move || {
+                    fs::write(&path, format!("fn main() {{ {i} }}")).unwrap();
+                    for attempt in 0..10 {
+                        data_gather.failed(&path, &format!("error {attempt}"));
+                    }
+                    data_gather.passed(&path);
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let data = fs::read_to_string(dir.join("data.jsonl")).unwrap();
+        let _ignored = fs::remove_dir_all(&dir);
+        let lines: Vec<_> = data.lines().filter(|line| !line.is_empty()).collect();
+        assert_eq!(lines.len(), 80);
+        for line in lines {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_record_to_json_escapes() {
+        let dir = std::env::temp_dir().join(format!("rustlings_to_json_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("exercise.rs");
+        let mut record = Record::new(&path);
+
+        fs::write(&path, "fn main() {\n\tlet path = \"C:\\dir\";\n}").unwrap();
+        record.set_error("error: \"C:\\dir\"\n\tnot found");
+        fs::write(&path, "fn main() {}").unwrap();
+        record.read_right_code();
+        let json = record.to_json().unwrap();
+        let _ignored = fs::remove_dir_all(&dir);
+
+        // Parsed back as written, which seed-data relies on to skip
+        // exercises that already have records
+        let line: serde_json::Value = serde_json::from_str(json.trim_end()).unwrap();
+        assert_eq!(
+            line["wrong_code"],
+            "fn main() {\n\tlet path = \"C:\\dir\";\n}"
+        );
+        assert_eq!(line["error"], "error: \"C:\\dir\"\n\tnot found");
+        assert_eq!(line["right_code"], "fn main() {}");
+        assert_eq!(line["path"], path.display().to_string());
+    }
+
+    #[test]
+    fn test_record_all_attempts() {
+        let dir = std::env::temp_dir().join(format!("rustlings_attempts_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("exercise.rs");
+        let data_gather = DataGather::new(dir.join("data.jsonl")).with_attempts(true);
+
+        fs::write(&path, "fn main() { \"\\t\" }").unwrap();
+        data_gather.failed(&path, "error: C:\\path \"quoted\"\n\ttabbed");
+        data_gather.failed(&path, "error: C:\\path \"quoted\"\n\ttabbed");
+        fs::write(&path, "fn main() {}").unwrap();
+        data_gather.failed(&path, "error: other");
+        data_gather.passed(&path);
+
+        let data = fs::read_to_string(dir.join("data.jsonl")).unwrap();
+        let _ignored = fs::remove_dir_all(&dir);
+        let records: Vec<serde_json::Value> = data
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let attempts: Vec<_> = records.iter().filter(|r| r["attempt"] == true).collect();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0]["error"], "error: C:\\path \"quoted\"\n\ttabbed");
+        assert_eq!(attempts[0]["content_hash"], attempts[1]["content_hash"]);
+        assert_ne!(attempts[1]["content_hash"], attempts[2]["content_hash"]);
+        // The distinct errors are still recorded with the solution
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[3]["wrong_code"], "fn main() { \"\\t\" }");
+        assert_eq!(records[4]["right_code"], "fn main() {}");
+    }
+
+    #[test]
+    fn test_efforts() {
+        let dir = std::env::temp_dir().join(format!("rustlings_efforts_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data = [
+            r#"{"attempt":true,"path":"a.rs","error":"e","timestamp":100}"#,
+            r#"{"attempt":true,"path":"a.rs","error":"e","timestamp":160}"#,
+            r#"{"hint_viewed":true,"path":"a.rs","timestamp":130}"#,
+            r#"{"wrong_code":"","error":"e","right_code":"","path":"a.rs"}"#,
+            r#"{"wrong_code":"x","error":"e1","right_code":"y","path":"b.rs"}"#,
+            r#"{"wrong_code":"x","error":"e2","right_code":"y","path":"b.rs"}"#,
+        ];
+        fs::write(dir.join("data.jsonl"), data.join("\n")).unwrap();
+        let efforts = DataGather::new(dir.join("data.jsonl")).efforts().unwrap();
+        let _ignored = fs::remove_dir_all(&dir);
+
+        let effort = |attempts, seconds| Effort { attempts, seconds };
+        assert_eq!(efforts["a.rs"], effort(2, 60));
+        assert_eq!(efforts["b.rs"], effort(2, 0));
+    }
+
+    #[test]
+    fn test_hint_views() {
+        let dir = std::env::temp_dir().join(format!("rustlings_hints_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data_gather = DataGather::new(dir.join("data.jsonl"));
+        assert!(data_gather.hint_views().unwrap().is_empty());
+
+        let path = Path::new("exercises/intro/intro1.rs");
+        data_gather.hint_viewed(path).unwrap();
+        let first = data_gather.hint_views().unwrap();
+        data_gather.hint_viewed(path).unwrap();
+        data_gather
+            .hint_viewed(Path::new("exercises/intro/intro2.rs"))
+            .unwrap();
+        let views = data_gather.hint_views().unwrap();
+        let _ignored = fs::remove_dir_all(&dir);
+
+        assert_eq!(first["exercises/intro/intro1.rs"], 1);
+        assert_eq!(views["exercises/intro/intro1.rs"], 2);
+        assert_eq!(views["exercises/intro/intro2.rs"], 1);
     }
 }
@@ -1,16 +1,28 @@
+use glob::{MatchOptions, Pattern};
 use regex::Regex;
+use semver::Version;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::error::Error;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Child, Command, ExitStatus, Output, Stdio};
+use std::str::FromStr;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
-const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
 const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
+// The edition of Clippy exercises that don't ask for one
+const CLIPPY_EDITION: &str = "2021";
+// The seconds an exercise stopped for running too long gets to exit
+const DEFAULT_KILL_TIMEOUT: u64 = 5;
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -24,25 +36,626 @@ fn temp_file() -> String {
 }
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Copy, Clone, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
+    #[default]
     Compile,
     // Indicates that the exercise should be compiled as a test harness
     Test,
     // Indicates that the exercise should be linted with clippy
     Clippy,
+    // Indicates that the exercise should be run and its output compared
+    // against the expected output
+    Output,
+}
+
+// The panic strategy an exercise is compiled with
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Panic {
+    Abort,
+    Unwind,
+}
+
+impl Panic {
+    fn as_str(self) -> &'static str {
+        match self {
+            Panic::Abort => "abort",
+            Panic::Unwind => "unwind",
+        }
+    }
+}
+
+impl FromStr for Panic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abort" => Ok(Panic::Abort),
+            "unwind" => Ok(Panic::Unwind),
+            _ => Err(format!(
+                "unknown panic strategy '{s}', expected \"abort\" or \"unwind\""
+            )),
+        }
+    }
+}
+
+// The formats a manifest can be written in, told apart by its extension
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ManifestFormat {
+    Toml,
+    Yaml,
+}
+
+impl ManifestFormat {
+    // A `.yaml` or `.yml` manifest is YAML, anything else TOML
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => ManifestFormat::Yaml,
+            _ => ManifestFormat::Toml,
+        }
+    }
+
+    // The top-level manifest in this format
+    pub fn info_path(self) -> &'static str {
+        match self {
+            ManifestFormat::Toml => "info.toml",
+            ManifestFormat::Yaml => "info.yaml",
+        }
+    }
+
+    fn parse<T: DeserializeOwned>(self, source: &str) -> Result<T, String> {
+        match self {
+            ManifestFormat::Toml => toml::from_str(source).map_err(|e| e.to_string()),
+            ManifestFormat::Yaml => serde_yaml::from_str(source).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+impl FromStr for ManifestFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "toml" => Ok(ManifestFormat::Toml),
+            "yaml" => Ok(ManifestFormat::Yaml),
+            _ => Err(format!(
+                "unknown manifest format '{s}', expected \"toml\" or \"yaml\""
+            )),
+        }
+    }
 }
 
 #[derive(Deserialize)]
 pub struct ExerciseList {
+    #[serde(default)]
     pub exercises: Vec<Exercise>,
+    // Further manifests whose exercises are appended to this list.
+    // These paths are relative to the manifest that includes them.
+    #[serde(default)]
+    pub include: Vec<PathBuf>,
+    // The oldest rustlings binary able to run these exercises.
+    // The highest version required by any included manifest wins.
+    #[serde(default)]
+    pub min_rustlings_version: Option<Version>,
+    // Settings shared by the exercises in a directory
+    #[serde(default)]
+    pub directories: Vec<Directory>,
+    // Replace the built-in banner shown before the introduction, the
+    // message shown once every exercise is done, and the introduction
+    // shown without a command. Those of the including manifest win.
+    #[serde(default)]
+    pub welcome_message: Option<String>,
+    #[serde(default)]
+    pub final_message: Option<String>,
+    #[serde(default)]
+    pub default_message: Option<String>,
+    // The names of the exercises to verify and list first, in this order,
+    // the others follow in manifest order. That of the including manifest wins.
+    #[serde(default)]
+    pub order: Vec<String>,
+    // The numbers of solved exercises celebrated in watch mode, along with
+    // the first exercise, each chapter and the last exercise
+    #[serde(default)]
+    pub milestones: Option<Vec<usize>>,
+}
+
+// Why an exercise comes where it does, given the `order` of the manifest,
+// along with the exercises to solve first
+pub fn order_reason(order: &[String], exercise: &Exercise) -> String {
+    let mut reason = match order.iter().position(|name| *name == exercise.name) {
+        Some(index) => format!("number {} in the order of the manifest", index + 1),
+        None if order.is_empty() => String::from("manifest order"),
+        None => String::from("manifest order, after the exercises of the order"),
+    };
+    if !exercise.prerequisites.is_empty() {
+        reason.push_str(&format!(", requires {}", exercise.prerequisites.join(", ")));
+    }
+    reason
+}
+
+// The compiler exercises are verified with, as far as they care
+#[derive(PartialEq, Debug)]
+pub struct Toolchain {
+    pub nightly: bool,
+    // The target the compiler builds for by default
+    pub host: String,
+}
+
+impl Toolchain {
+    pub fn detect(compiler: &Compiler) -> io::Result<Self> {
+        let version = compiler.version()?;
+        Toolchain::parse(&version)
+            .ok_or_else(|| io::Error::other(format!("unexpected rustc -vV output: {version}")))
+    }
+
+    // Read the output of `rustc -vV`
+    fn parse(version: &str) -> Option<Self> {
+        let field = |name: &str| {
+            version
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+        };
+        let release = field("release")?;
+        Some(Toolchain {
+            nightly: release.contains("-nightly") || release.contains("-dev"),
+            host: field("host")?.to_string(),
+        })
+    }
+}
+
+// Defaults for the exercises below a directory, like
+// [[directories]]
+// path = "exercises/async"
+// edition = "2021"
+#[derive(Deserialize)]
+pub struct Directory {
+    pub path: PathBuf,
+    // The edition the exercises are compiled with unless they set their own
+    #[serde(default)]
+    pub edition: Option<String>,
+}
+
+impl ExerciseList {
+    // Load the exercise list from the given manifest,
+    // merging in the exercises of all the manifests it includes
+    pub fn load(path: &Path) -> Result<ExerciseList, Box<dyn Error>> {
+        let mut list = ExerciseList {
+            exercises: Vec::new(),
+            include: Vec::new(),
+            min_rustlings_version: None,
+            directories: Vec::new(),
+            welcome_message: None,
+            final_message: None,
+            default_message: None,
+            order: Vec::new(),
+            milestones: None,
+        };
+        let mut visited = HashSet::new();
+        list.merge(path, &mut visited)?;
+        list.resolve_editions();
+        list.apply_order()?;
+        list.check_prerequisites()?;
+        Ok(list)
+    }
+
+    fn merge(&mut self, path: &Path, visited: &mut HashSet<PathBuf>) -> Result<(), Box<dyn Error>> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if !visited.insert(canonical) {
+            return Err(format!("{} is included more than once", path.display()).into());
+        }
+
+        let source = fs::read_to_string(path)?;
+        let list = ManifestFormat::of(path)
+            .parse::<ExerciseList>(&source)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+        if list.min_rustlings_version > self.min_rustlings_version {
+            self.min_rustlings_version = list.min_rustlings_version;
+        }
+        self.welcome_message = self.welcome_message.take().or(list.welcome_message);
+        self.final_message = self.final_message.take().or(list.final_message);
+        self.default_message = self.default_message.take().or(list.default_message);
+        if self.order.is_empty() {
+            self.order = list.order;
+        }
+        self.milestones = self.milestones.take().or(list.milestones);
+
+        for mut exercise in list.exercises {
+            exercise.path = normalize_path(&exercise.path);
+            if exercise.hint.is_some() == exercise.hint_file.is_some() {
+                return Err(format!(
+                    "The exercise '{}' in {} needs either a hint or a hint_file",
+                    exercise.name,
+                    path.display()
+                )
+                .into());
+            }
+            if let Some(other) = self.exercises.iter().find(|e| e.path == exercise.path) {
+                return Err(format!(
+                    "The exercises '{}' and '{}' in {} share the file {}",
+                    other.name,
+                    exercise.name,
+                    path.display(),
+                    exercise.path.display()
+                )
+                .into());
+            }
+            if self.exercises.iter().any(|e| e.name == exercise.name) {
+                return Err(format!(
+                    "The exercise '{}' in {} is already defined",
+                    exercise.name,
+                    path.display()
+                )
+                .into());
+            }
+            self.exercises.push(exercise);
+        }
+
+        for mut directory in list.directories {
+            directory.path = normalize_path(&directory.path);
+            self.directories.push(directory);
+        }
+
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+        for include in list.include {
+            self.merge(&parent.join(&include), visited)?;
+            self.include.push(include);
+        }
+        Ok(())
+    }
+
+    // Move the exercises named in `order` to the front, in that order
+    fn apply_order(&mut self) -> Result<(), Box<dyn Error>> {
+        let unknown: Vec<&str> = self
+            .order
+            .iter()
+            .filter(|name| !self.exercises.iter().any(|e| &e.name == *name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(
+                format!("The order lists unknown exercises: {}", unknown.join(", ")).into(),
+            );
+        }
+        let position = |exercise: &Exercise| {
+            self.order
+                .iter()
+                .position(|name| *name == exercise.name)
+                .unwrap_or(self.order.len())
+        };
+        // A stable sort keeps the unlisted exercises in manifest order
+        let mut exercises = std::mem::take(&mut self.exercises);
+        exercises.sort_by_key(position);
+        self.exercises = exercises;
+        Ok(())
+    }
+
+    fn check_prerequisites(&self) -> Result<(), Box<dyn Error>> {
+        for exercise in &self.exercises {
+            let unknown: Vec<&str> = exercise
+                .prerequisites
+                .iter()
+                .filter(|name| !self.exercises.iter().any(|e| &e.name == *name))
+                .map(String::as_str)
+                .collect();
+            if !unknown.is_empty() {
+                return Err(format!(
+                    "The exercise '{}' requires unknown exercises: {}",
+                    exercise.name,
+                    unknown.join(", ")
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    // Give the exercises without an edition the one of the innermost
+    // directory containing them that sets one
+    fn resolve_editions(&mut self) {
+        for exercise in &mut self.exercises {
+            if exercise.edition.is_some() {
+                continue;
+            }
+            exercise.edition = self
+                .directories
+                .iter()
+                .filter(|d| d.edition.is_some() && exercise.path.starts_with(&d.path))
+                .max_by_key(|d| d.path.components().count())
+                .and_then(|d| d.edition.clone());
+        }
+    }
+
+    // The version required by the exercises when the running `version` is older
+    pub fn required_version(&self, version: &Version) -> Option<&Version> {
+        self.min_rustlings_version
+            .as_ref()
+            .filter(|required| *required > version)
+    }
+}
+
+// Normalize a manifest path so it compares equal however it was written:
+// backslashes become separators and `.`, empty and `..` components are
+// resolved without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut normalized = PathBuf::new();
+    for component in Path::new(&path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// The tests of an exercise: the item following the first `#[cfg(test)]`
+// up to its closing brace, or everything from the first `#[test]` when
+// there is no test module. Line endings and trailing whitespace are
+// normalized so that the hash doesn't depend on the platform. Braces are
+// counted without regard to strings or comments, which exercises' test
+// modules don't put them in.
+fn test_block(source: &str) -> Option<String> {
+    let start = source
+        .find("#[cfg(test)]")
+        .or_else(|| source.find("#[test]"))?;
+    let block = &source[start..];
+    let mut end = block.len();
+    if block.starts_with("#[cfg(test)]") {
+        let mut depth = 0;
+        for (i, c) in block.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Some(
+        block[..end]
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+// The compiler colors its output unless `--color never` turned colors off.
+// Where its output is captured rather than shown, the colors are stripped.
+fn rustc_color_args() -> [&'static str; 2] {
+    let when = if crate::ui::no_color() {
+        "never"
+    } else {
+        "always"
+    };
+    ["--color", when]
+}
+
+// The manifest entry of the exercise `name`, searching the included
+// manifests too. Read as plain values so that entries the exercise list
+// rejects can still be inspected.
+fn find_entry(
+    manifest: &Path,
+    name: &str,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<Option<toml::value::Table>, Box<dyn Error>> {
+    if !visited.insert(manifest.canonicalize()?) {
+        return Ok(None);
+    }
+    let value: toml::Value = ManifestFormat::of(manifest)
+        .parse(&fs::read_to_string(manifest)?)
+        .map_err(|e| format!("Failed to parse {}: {e}", manifest.display()))?;
+    let entries = value.get("exercises").and_then(|e| e.as_array());
+    for entry in entries.into_iter().flatten() {
+        if let Some(table) = entry.as_table() {
+            if table.get("name").and_then(|n| n.as_str()) == Some(name) {
+                return Ok(Some(table.clone()));
+            }
+        }
+    }
+    let parent = manifest.parent().unwrap_or_else(|| Path::new(""));
+    let includes = value.get("include").and_then(|i| i.as_array());
+    for include in includes.into_iter().flatten().filter_map(|i| i.as_str()) {
+        if let Some(table) = find_entry(&parent.join(include), name, visited)? {
+            return Ok(Some(table));
+        }
+    }
+    Ok(None)
+}
+
+// Everything wrong with the exercise `name` that its author should fix
+// before submitting it, empty if nothing is
+pub fn check_exercise(manifest: &Path, name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let entry = find_entry(manifest, name, &mut HashSet::new())?
+        .ok_or_else(|| format!("No exercise found for '{name}'"))?;
+    let text = |key: &str| entry.get(key).and_then(|value| value.as_str());
+    let mut problems = Vec::new();
+
+    let path = text("path").map(Path::new);
+    let source = match path {
+        None => {
+            problems.push("It has no path".to_string());
+            None
+        }
+        Some(path) => match fs::read_to_string(path) {
+            Ok(source) => Some(source),
+            Err(e) => {
+                problems.push(format!("Its file {} can't be read: {e}", path.display()));
+                None
+            }
+        },
+    };
+
+    let mode = text("mode");
+    match mode {
+        None => problems.push("It has no mode".to_string()),
+        Some("compile" | "test" | "clippy" | "output") => {}
+        Some(mode) => problems.push(format!(
+            "Its mode '{mode}' isn't one of compile, test, clippy or output"
+        )),
+    }
+
+    let hint = match (text("hint"), text("hint_file")) {
+        (Some(_), Some(_)) => {
+            problems.push("It sets both a hint and a hint_file".to_string());
+            None
+        }
+        (Some(hint), None) => Some(hint.to_string()),
+        (None, Some(hint_file)) => {
+            let dir = path.and_then(Path::parent).unwrap_or_else(|| Path::new(""));
+            let hint_path = dir.join(hint_file);
+            match fs::read_to_string(&hint_path) {
+                Ok(hint) => Some(hint),
+                Err(e) => {
+                    let display = hint_path.display();
+                    problems.push(format!("Its hint_file {display} can't be read: {e}"));
+                    None
+                }
+            }
+        }
+        (None, None) => Some(String::new()),
+    };
+    if hint.is_some_and(|hint| hint.trim().is_empty()) {
+        problems.push("Its hint is empty".to_string());
+    }
+
+    if let Some(source) = source {
+        if !Regex::new(I_AM_DONE_REGEX).unwrap().is_match(&source) {
+            problems.push("Its file has no `I AM NOT DONE` marker".to_string());
+        }
+        if mode == Some("test") && !source.contains("#[test]") {
+            problems.push("It is in test mode but its file has no #[test] function".to_string());
+        }
+    }
+    Ok(problems)
+}
+
+// The command compiling the exercises: rustc itself, or a wrapper given
+// the rustc arguments after its own like `sccache rustc`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Compiler {
+    program: String,
+    args: Vec<String>,
+    // The rustup toolchain to compile with, the active one when unset
+    toolchain: Option<String>,
+    // Flags given to every compilation, taken from RUSTFLAGS
+    rustflags: Vec<String>,
+    // Flags given to rustdoc when Cargo runs it, taken from RUSTDOCFLAGS
+    rustdocflags: Vec<String>,
+}
+
+impl Compiler {
+    pub fn with_toolchain(self, toolchain: Option<String>) -> Self {
+        Compiler { toolchain, ..self }
+    }
+
+    pub fn with_flags(self, rustflags: Vec<String>, rustdocflags: Vec<String>) -> Self {
+        Compiler {
+            rustflags,
+            rustdocflags,
+            ..self
+        }
+    }
+
+    pub fn command(&self) -> Command {
+        let mut command = match &self.toolchain {
+            Some(toolchain) => {
+                let mut command = Command::new("rustup");
+                command.args(["run", toolchain, &self.program]);
+                command
+            }
+            None => Command::new(&self.program),
+        };
+        command.args(&self.args).args(&self.rustflags);
+        command
+    }
+
+    // The output of `rustc -vV`, which tells the toolchain apart
+    pub fn version(&self) -> io::Result<String> {
+        let output = self.command().arg("-vV").output()?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    // Cargo, for the exercises compiled as a package, with the same
+    // toolchain and flags
+    fn cargo(&self) -> Command {
+        let mut command = Command::new("cargo");
+        if let Some(toolchain) = &self.toolchain {
+            command.arg(format!("+{toolchain}"));
+        }
+        command
+            .env("RUSTFLAGS", self.rustflags.join(" "))
+            .env("RUSTDOCFLAGS", self.rustdocflags.join(" "));
+        command
+    }
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Compiler {
+            program: String::from("rustc"),
+            args: Vec::new(),
+            toolchain: None,
+            rustflags: Vec::new(),
+            rustdocflags: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Compiler {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace().map(String::from);
+        match words.next() {
+            Some(program) => Ok(Compiler {
+                program,
+                args: words.collect(),
+                ..Compiler::default()
+            }),
+            None => Err(String::from(
+                "empty compiler, expected a command like \"rustc\" or \"sccache rustc\"",
+            )),
+        }
+    }
+}
+
+// The options given on the command line that apply to every exercise,
+// kept apart from the exercises as the manifest describes them
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    // The command compiling the exercises, from `--compiler` and `--toolchain`
+    pub compiler: Compiler,
+    // Whether compiler warnings fail the exercises that don't allow them,
+    // from `--deny-warnings`
+    pub deny_warnings: bool,
+    // The seconds a stopped exercise gets to exit before it is killed,
+    // from `--kill-timeout`
+    pub kill_timeout: Option<u64>,
+    // Run the exercises under Miri to detect undefined behavior, from `--miri`
+    pub miri: bool,
+    // Only run the tests whose name contains this, from `run --test`
+    pub test_filter: Option<String>,
+    // Only compile the tests of a test mode exercise, from `run --no-run`
+    pub no_run: bool,
 }
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -51,7 +664,89 @@ pub struct Exercise {
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
     // The hint text associated with the exercise
-    pub hint: String,
+    #[serde(default)]
+    pub hint: Option<String>,
+    // A file containing the hint, relative to the exercise,
+    // for hints too long to keep in the manifest
+    #[serde(default)]
+    pub hint_file: Option<PathBuf>,
+    // What the exercise teaches, shown by `about` before starting it
+    #[serde(default)]
+    pub description: Option<String>,
+    // The concepts the learner should take away from the exercise
+    #[serde(default)]
+    pub learning_goals: Vec<String>,
+    // The chapter of the Rust book covering the exercise, a link or
+    // a page of the book like "ch05-01-defining-structs.html"
+    #[serde(default)]
+    pub book_ref: Option<String>,
+    // The standard output expected from an exercise in output mode
+    #[serde(default)]
+    pub expected_output: Option<String>,
+    // A file containing the expected output, relative to the exercise
+    #[serde(default)]
+    pub expected_output_path: Option<PathBuf>,
+    // The number of threads the test harness of a test mode exercise
+    // may use, the harness default is used when unset
+    #[serde(default)]
+    pub test_threads: Option<usize>,
+    // Also run the tests marked `#[ignore]`, for test mode exercises whose
+    // learners have to enable and solve them
+    #[serde(default)]
+    pub require_ignored: bool,
+    // How much the exercise is worth when grading, 1 when unset
+    #[serde(default)]
+    pub points: Option<u32>,
+    // Freeform concepts practiced by the exercise, like "ownership"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // The names of the exercises to solve before this one
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    // The chapter the exercise belongs to, like "structs", grouping it in
+    // `stats --per-topic`
+    #[serde(default)]
+    pub topic: Option<String>,
+    // The Rust edition to compile the exercise with, falling back to the
+    // edition of its directory and then to the compiler's default
+    #[serde(default)]
+    pub edition: Option<String>,
+    // Keep warnings as warnings even with `--deny-warnings`, for exercises
+    // that warn on purpose (like ones demonstrating dead code)
+    #[serde(default)]
+    pub allow_warnings: bool,
+    // The panic strategy to compile the exercise with, the compiler's
+    // default (unwind) when unset
+    #[serde(default)]
+    pub panic: Option<Panic>,
+    // Environment variables of the running exercise, on top of (and
+    // overriding) the ones rustlings was started with
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    // Stop the running exercise after this many seconds, falling back to
+    // `--timeout`, it may run for as long as it likes when both are unset
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // A work in progress, left out of `verify`, `watch` and `list`
+    // unless they're given `--include-ignored`
+    #[serde(default)]
+    pub ignored: bool,
+    // Only verify the exercise with a nightly compiler, it's skipped otherwise
+    #[serde(default)]
+    pub requires_nightly: bool,
+    // Only verify the exercise on this host target, like
+    // "x86_64-unknown-linux-gnu", it's skipped on the others
+    #[serde(default)]
+    pub target: Option<String>,
+    // The SHA-256 of the exercise's tests as shipped, to tell when a
+    // learner changed them instead of the code they check
+    #[serde(default)]
+    pub starter_sha256: Option<String>,
+    // A shell command checking the exercise instead of compiling it, like
+    // "rustfmt --check {path}", that passes when it exits with 0. `{path}`
+    // is replaced with the path of the exercise.
+    #[serde(default)]
+    pub check_command: Option<String>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -78,13 +773,14 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
+    options: &'a RunOptions,
     _handle: FileHandle,
 }
 
 impl<'a> CompiledExercise<'a> {
     // Run the compiled exercise
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        self.exercise.run(self.options)
     }
 }
 
@@ -95,6 +791,23 @@ pub struct ExerciseOutput {
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // The exit code of the binary, None if it was killed by a signal
+    pub exit_code: Option<i32>,
+    // The signal that terminated the binary, if it was killed by one
+    pub signal: Option<i32>,
+}
+
+impl ExerciseOutput {
+    // A human readable description of the terminating signal, if any
+    pub fn signal_description(&self) -> Option<String> {
+        self.signal.map(|signal| {
+            #[cfg(unix)]
+            let name = signal_hook::low_level::signal_name(signal).unwrap_or("unknown signal");
+            #[cfg(not(unix))]
+            let name = "unknown signal";
+            format!("process killed by signal {signal} ({name})")
+        })
+    }
 }
 
 struct FileHandle;
@@ -106,28 +819,62 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    // The link to the chapter of the Rust book covering the exercise
+    pub fn book_link(&self) -> Option<String> {
+        self.book_ref.as_ref().map(|book_ref| {
+            if book_ref.starts_with("https://") || book_ref.starts_with("http://") {
+                book_ref.clone()
+            } else {
+                format!("https://doc.rust-lang.org/book/{book_ref}")
+            }
+        })
+    }
+
+    // The compiler invocation shared by every mode
+    fn rustc(&self, options: &RunOptions) -> Command {
+        let mut command = options.compiler.command();
+        if let Some(edition) = &self.edition {
+            command.args(["--edition", edition]);
+        }
+        if options.deny_warnings && !self.allow_warnings {
+            command.args(["-D", "warnings"]);
+        }
+        if let Some(panic) = self.panic {
+            command.args(["-C", &format!("panic={}", panic.as_str())]);
+        }
+        command
+    }
+
+    pub fn compile<'a>(
+        &'a self,
+        options: &'a RunOptions,
+    ) -> Result<CompiledExercise<'a>, ExerciseOutput> {
         let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+            Mode::Compile | Mode::Output => self
+                .rustc(options)
+                .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(rustc_color_args())
                 .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+            Mode::Test => self
+                .rustc(options)
+                .args(["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(rustc_color_args())
                 .output(),
             Mode::Clippy => {
                 let cargo_toml = format!(
                     r#"[package]
 name = "{}"
 version = "0.0.1"
-edition = "2021"
+edition = "{}"
 [[bin]]
 name = "{}"
 path = "{}.rs""#,
-                    self.name, self.name, self.name
+                    self.name,
+                    self.edition.as_deref().unwrap_or(CLIPPY_EDITION),
+                    self.name,
+                    self.name
                 );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
+                let cargo_toml_error_msg = if crate::ui::no_emoji() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
@@ -137,24 +884,28 @@ path = "{}.rs""#,
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
+                self.rustc(options)
+                    .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args(rustc_color_args())
                     .output()
                     .expect("Failed to compile!");
                 // Due to an issue with Clippy, a cargo clean is required to catch all lints.
                 // See https://github.com/rust-lang/rust-clippy/issues/2604
                 // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
                 // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
+                options
+                    .compiler
+                    .cargo()
+                    .args(["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .args(rustc_color_args())
                     .output()
                     .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                options
+                    .compiler
+                    .cargo()
+                    .args(["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .args(rustc_color_args())
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
                     .output()
             }
         }
@@ -163,6 +914,7 @@ path = "{}.rs""#,
         if cmd.status.success() {
             Ok(CompiledExercise {
                 exercise: self,
+                options,
                 _handle: FileHandle,
             })
         } else {
@@ -170,26 +922,143 @@ path = "{}.rs""#,
             Err(ExerciseOutput {
                 stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
                 stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                exit_code: cmd.status.code(),
+                signal: exit_signal(&cmd.status),
             })
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            _ => "",
+    // Run the exercise, or its tests in test mode, under Miri to detect
+    // undefined behavior. Miri only runs Cargo packages, so the exercise
+    // is copied into a temporary one.
+    pub fn run_miri(&self, options: &RunOptions) -> io::Result<ExerciseOutput> {
+        let package = env::temp_dir().join(format!("rustlings_miri_{}", process::id()));
+        let _ignored = fs::remove_dir_all(&package);
+        fs::create_dir_all(package.join("src"))?;
+        let cargo_toml = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.0.1\"\nedition = \"{}\"\n",
+            self.name.replace(|c: char| !c.is_alphanumeric(), "_"),
+            self.edition.as_deref().unwrap_or(CLIPPY_EDITION)
+        );
+        fs::write(package.join("Cargo.toml"), cargo_toml)?;
+        let (target, subcommand) = match self.mode {
+            Mode::Test => ("lib.rs", "test"),
+            _ => ("main.rs", "run"),
         };
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        fs::copy(&self.path, package.join("src").join(target))?;
 
-        let output = ExerciseOutput {
+        let cmd = options
+            .compiler
+            .cargo()
+            .args(["miri", subcommand, "--quiet"])
+            .args(rustc_color_args())
+            .current_dir(&package)
+            .envs(&self.env)
+            .output();
+        let _ignored = fs::remove_dir_all(&package);
+        let cmd = cmd?;
+        Ok(ExerciseOutput {
             stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
             stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            exit_code: cmd.status.code(),
+            signal: exit_signal(&cmd.status),
+        })
+    }
+
+    // Check the exercise without building it, returning the compiler's
+    // JSON diagnostics. Clippy lints are not part of them.
+    pub fn check_json(&self, options: &RunOptions) -> io::Result<String> {
+        let mut command = self.rustc(options);
+        if let Mode::Test = self.mode {
+            command.arg("--test");
+        }
+        let output = command
+            .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+            .args(["--error-format=json", "--emit=metadata"])
+            .output();
+        clean();
+        Ok(String::from_utf8_lossy(&output?.stderr).into_owned())
+    }
+
+    // The arguments passed to the compiled exercise when running it
+    fn run_args(&self, options: &RunOptions) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Mode::Test = self.mode {
+            args.push(String::from("--show-output"));
+            if let Some(threads) = self.test_threads {
+                args.push(format!("--test-threads={threads}"));
+            }
+            if self.require_ignored {
+                args.push(String::from("--include-ignored"));
+            }
+            if let Some(filter) = &options.test_filter {
+                args.push(filter.clone());
+            }
+        }
+        args
+    }
+
+    fn run(&self, options: &RunOptions) -> Result<ExerciseOutput, ExerciseOutput> {
+        let mut command = Command::new(temp_file());
+        command.args(self.run_args(options));
+        self.output(command, options)
+    }
+
+    // Run the `check_command` of the exercise through the shell
+    pub fn run_check_command(
+        &self,
+        check_command: &str,
+        options: &RunOptions,
+    ) -> Result<ExerciseOutput, ExerciseOutput> {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
         };
+        let path = self.path.to_string_lossy();
+        let mut command = Command::new(shell);
+        command
+            .args([flag, &check_command.replace("{path}", &path)])
+            .env("RUSTLINGS_EXERCISE", &self.name)
+            .env("RUSTLINGS_EXERCISE_PATH", &self.path);
+        self.output(command, options)
+    }
 
-        if cmd.status.success() {
+    // Run a command of the exercise with its environment and timeout
+    fn output(
+        &self,
+        mut command: Command,
+        options: &RunOptions,
+    ) -> Result<ExerciseOutput, ExerciseOutput> {
+        command.envs(&self.env);
+        let (cmd, timed_out) = match self.timeout {
+            Some(timeout) => {
+                let grace = options.kill_timeout.unwrap_or(DEFAULT_KILL_TIMEOUT);
+                output_within(
+                    command,
+                    Duration::from_secs(timeout),
+                    Duration::from_secs(grace),
+                )
+            }
+            None => command.output().map(|output| (output, false)),
+        }
+        .expect("Failed to run 'run' command");
+
+        let mut output = ExerciseOutput {
+            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            exit_code: cmd.status.code(),
+            signal: exit_signal(&cmd.status),
+        };
+
+        if timed_out {
+            output.stderr.push_str(&format!(
+                "\n{} was stopped after running for {} seconds\n",
+                self.name,
+                self.timeout.unwrap_or_default()
+            ));
+            Err(output)
+        } else if cmd.status.success() {
             Ok(output)
         } else {
             Err(output)
@@ -237,6 +1106,133 @@ path = "{}.rs""#,
         State::Pending(context)
     }
 
+    // The hint of the exercise, read from its hint file if it has one
+    pub fn read_hint(&self) -> io::Result<String> {
+        match (&self.hint, &self.hint_file) {
+            (Some(hint), _) => Ok(hint.clone()),
+            (None, Some(hint_file)) => {
+                let path = self.path.parent().unwrap_or(&self.path).join(hint_file);
+                Ok(fs::read_to_string(path)?.trim_end().to_string())
+            }
+            (None, None) => Ok(String::new()),
+        }
+    }
+
+    // The SHA-256 of the tests in the exercise file, as lowercase hex,
+    // None if it has none
+    pub fn test_block_sha256(&self) -> io::Result<Option<String>> {
+        let source = fs::read_to_string(&self.path)?;
+        Ok(test_block(&source).map(|block| {
+            Sha256::digest(block.as_bytes())
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect()
+        }))
+    }
+
+    // Whether the tests differ from the ones the exercise shipped with,
+    // None when it doesn't record their hash
+    pub fn tests_altered(&self) -> io::Result<Option<bool>> {
+        let Some(expected) = &self.starter_sha256 else {
+            return Ok(None);
+        };
+        let actual = self.test_block_sha256()?;
+        Ok(Some(actual.as_deref() != Some(expected.as_str())))
+    }
+
+    // The standard output an exercise in output mode should print,
+    // either given inline or read from its golden file
+    pub fn expected_output(&self) -> Option<String> {
+        if let Some(expected) = &self.expected_output {
+            return Some(expected.clone());
+        }
+        fs::read_to_string(self.golden_path()).ok()
+    }
+
+    // The file holding the expected output: `expected_output_path` relative
+    // to the exercise, or the exercise path with an `.out` extension where
+    // `run --bless` writes it
+    pub fn golden_path(&self) -> PathBuf {
+        match &self.expected_output_path {
+            Some(path) => self.path.parent().unwrap_or(&self.path).join(path),
+            None => self.path.with_extension("out"),
+        }
+    }
+
+    // Whether a `list --filter` term selects this exercise. Terms containing
+    // glob metacharacters are matched against the path or any of its trailing
+    // parts, other terms are substrings of the name or the path.
+    pub fn matches_filter(&self, term: &str) -> bool {
+        if !term.contains(['*', '?', '[']) {
+            return self.name.contains(term) || self.path.to_string_lossy().contains(term);
+        }
+        let Ok(pattern) = Pattern::new(term) else {
+            return false;
+        };
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        let components: Vec<_> = self.path.components().collect();
+        (0..components.len()).any(|skip| {
+            let suffix: PathBuf = components[skip..].iter().collect();
+            pattern.matches_path_with(&suffix, options)
+        })
+    }
+
+    // Where the reference solution of the exercise lives: the exercise path
+    // with its leading `exercises` directory replaced by `solutions`, or
+    // below `solutions` if it isn't in the exercises directory
+    pub fn solution_path(&self) -> PathBuf {
+        match self.path.strip_prefix("exercises") {
+            Ok(relative) => Path::new("solutions").join(relative),
+            Err(_) => Path::new("solutions").join(&self.path),
+        }
+    }
+
+    // How much the exercise is worth when grading
+    pub fn points(&self) -> u32 {
+        self.points.unwrap_or(1)
+    }
+
+    // Whether `--miri` applies to the exercise. Output and Clippy exercises
+    // are checked by their output and lints, which Miri doesn't look at.
+    pub fn runs_under_miri(&self, options: &RunOptions) -> bool {
+        options.miri && matches!(self.mode, Mode::Compile | Mode::Test)
+    }
+
+    // Whether the given path, as reported by the file watcher, is this exercise.
+    // Both sides are canonicalized so that exercises reached through a symlink
+    // (e.g. an exercises directory on a mounted drive) still match the resolved
+    // path of the event. If either can't be resolved, fall back to a suffix match.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        match (path.canonicalize(), self.path.canonicalize()) {
+            (Ok(changed), Ok(exercise)) => changed == exercise,
+            _ => path.ends_with(&self.path),
+        }
+    }
+
+    // The last modification time of the exercise file in seconds since
+    // the Unix epoch, or None if the file's metadata can't be read
+    pub fn modified(&self) -> Option<u64> {
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok()?;
+        modified
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|duration| duration.as_secs())
+    }
+
+    // What the exercise needs that the toolchain lacks, if anything
+    pub fn unmet_requirement(&self, toolchain: &Toolchain) -> Option<String> {
+        if self.requires_nightly && !toolchain.nightly {
+            return Some(String::from("requires nightly"));
+        }
+        match &self.target {
+            Some(target) if *target != toolchain.host => Some(format!("requires target {target}")),
+            _ => None,
+        }
+    }
+
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
@@ -254,26 +1250,185 @@ impl Display for Exercise {
     }
 }
 
+// The signal that terminated a process, only available on Unix
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+// Run a command like `Command::output`, asking it to terminate once it ran
+// for `timeout` and killing it if it is still running `grace` later. Also
+// returns whether it had to be stopped.
+fn output_within(
+    mut command: Command,
+    timeout: Duration,
+    grace: Duration,
+) -> io::Result<(Output, bool)> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Read the pipes while waiting, a child filling one would block
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+
+    let timed_out = wait_within(&mut child, timeout)?.is_none();
+    if timed_out {
+        terminate(&mut child)?;
+        if wait_within(&mut child, grace)?.is_none() {
+            child.kill()?;
+        }
+    }
+    // Reap the child so it doesn't linger as a zombie
+    let status = child.wait()?;
+    Ok((
+        Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        },
+        timed_out,
+    ))
+}
+
+fn read_to_end(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+// The exit status of the child, None if it is still running after `timeout`
+fn wait_within(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+// Ask the child to exit with SIGTERM, giving it a chance to clean up
+#[cfg(unix)]
+fn terminate(child: &mut Child) -> io::Result<()> {
+    let pid = child.id() as libc::pid_t;
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut Child) -> io::Result<()> {
+    child.kill()
+}
+
+// Whether `cargo miri` can be run with the current toolchain
+pub fn miri_available() -> bool {
+    Command::new("cargo")
+        .args(["miri", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
 #[inline]
 fn clean() {
-    let _ignored = remove_file(&temp_file());
+    let _ignored = remove_file(temp_file());
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::Path;
+
+    #[test]
+    fn test_panic_strategy_flag() {
+        let exercise = Exercise {
+            name: "panics".into(),
+            path: PathBuf::from("exercises/panics.rs"),
+            mode: Mode::Compile,
+            panic: Some(Panic::Abort),
+            ..Default::default()
+        };
+        let command = exercise.rustc(&RunOptions::default());
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["-C", "panic=abort"]);
+
+        let exercise = Exercise {
+            panic: None,
+            ..exercise
+        };
+        assert_eq!(exercise.rustc(&RunOptions::default()).get_args().count(), 0);
+        assert_eq!("unwind".parse(), Ok(Panic::Unwind));
+        assert!("halt".parse::<Panic>().is_err());
+    }
+
+    #[test]
+    fn test_compiler_wrapper() {
+        let exercise = Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/intro/intro1.rs"),
+            edition: Some("2021".into()),
+            ..Default::default()
+        };
+        let options = RunOptions {
+            compiler: "sccache  rustc".parse().unwrap(),
+            ..Default::default()
+        };
+        let command = exercise.rustc(&options);
+        assert_eq!(command.get_program(), "sccache");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["rustc", "--edition", "2021"]);
+
+        assert_eq!("rustc".parse(), Ok(Compiler::default()));
+        assert!(" ".parse::<Compiler>().is_err());
+    }
+
+    #[test]
+    fn test_toolchain_prefix() {
+        let exercise = Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/intro/intro1.rs"),
+            edition: Some("2021".into()),
+            ..Default::default()
+        };
+        let options = RunOptions {
+            compiler: Compiler::default().with_toolchain(Some("beta".into())),
+            ..Default::default()
+        };
+        let command = exercise.rustc(&options);
+        assert_eq!(command.get_program(), "rustup");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["run", "beta", "rustc", "--edition", "2021"]);
+
+        let cargo = options.compiler.cargo();
+        assert_eq!(cargo.get_args().collect::<Vec<_>>(), ["+beta"]);
+    }
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
+        File::create(temp_file()).unwrap();
         let exercise = Exercise {
             name: String::from("example"),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::from(""),
+            ..Default::default()
         };
-        let compiled = exercise.compile().unwrap();
+        let options = RunOptions::default();
+        let compiled = exercise.compile(&options).unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
     }
@@ -284,7 +1439,7 @@ mod test {
             name: "pending_exercise".into(),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         let state = exercise.state();
@@ -325,21 +1480,359 @@ mod test {
             name: "finished_exercise".into(),
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         assert_eq!(exercise.state(), State::Done);
     }
 
+    #[test]
+    fn test_load_included_manifests() {
+        let list = ExerciseList::load(Path::new("tests/fixture/include/info.toml")).unwrap();
+        let names: Vec<_> = list.exercises.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["base_exercise", "included_exercise", "nested_exercise"]
+        );
+    }
+
+    #[test]
+    fn test_altered_tests_detected() {
+        let dir = std::env::temp_dir().join(format!("rustlings_starter_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tests1.rs");
+        let starter = "fn double(x: i32) -> i32 {\n    todo!()\n}\n\n\
+                       #[cfg(test)]\nmod tests {\n    use super::*;\n\n    \
+                       #[test]\n    fn doubles() {\n        assert_eq!(double(2), 4);\n    }\n}\n";
+        fs::write(&path, starter).unwrap();
+        let mut exercise = Exercise {
+            name: "tests1".into(),
+            path: path.clone(),
+            mode: Mode::Test,
+            ..Default::default()
+        };
+        assert_eq!(exercise.tests_altered().unwrap(), None);
+        exercise.starter_sha256 = exercise.test_block_sha256().unwrap();
+
+        // Solving the exercise and changing line endings leaves the tests intact
+        let solved = starter.replace("todo!()", "x * 2").replace('\n', "\r\n");
+        fs::write(&path, solved).unwrap();
+        let intact = exercise.tests_altered().unwrap();
+        fs::write(
+            &path,
+            starter.replace("double(2), 4", "double(2), double(2)"),
+        )
+        .unwrap();
+        let altered = exercise.tests_altered().unwrap();
+        let _ignored = fs::remove_dir_all(&dir);
+
+        assert_eq!(intact, Some(false));
+        assert_eq!(altered, Some(true));
+    }
+
+    #[test]
+    fn test_load_yaml_manifest() {
+        let toml = ExerciseList::load(Path::new("tests/fixture/yaml/info.toml")).unwrap();
+        let yaml = ExerciseList::load(Path::new("tests/fixture/yaml/info.yaml")).unwrap();
+        assert_eq!(yaml.exercises.len(), 3);
+        assert_eq!(
+            format!("{:?}", yaml.exercises),
+            format!("{:?}", toml.exercises)
+        );
+        assert_eq!(yaml.order, toml.order);
+    }
+
+    #[test]
+    fn test_load_name_collision() {
+        let error = ExerciseList::load(Path::new("tests/fixture/include/collision.toml"))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("'base_exercise'"));
+    }
+
+    #[test]
+    fn test_load_applies_order() {
+        let list = ExerciseList::load(Path::new("tests/fixture/order/info.toml")).unwrap();
+        let names: Vec<_> = list.exercises.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["third", "first", "second", "fourth"]);
+
+        let error = ExerciseList::load(Path::new("tests/fixture/order/unknown.toml"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "The order lists unknown exercises: fifth, sixth"
+        );
+    }
+
+    #[test]
+    fn test_unmet_requirements() {
+        let stable = Toolchain::parse(
+            "rustc 1.95.0 (59807616e 2026-04-14)\nbinary: rustc\n\
+             release: 1.95.0\nhost: x86_64-unknown-linux-gnu\n",
+        )
+        .unwrap();
+        assert_eq!(
+            stable,
+            Toolchain {
+                nightly: false,
+                host: "x86_64-unknown-linux-gnu".into()
+            }
+        );
+        let nightly =
+            Toolchain::parse("release: 1.97.0-nightly\nhost: aarch64-apple-darwin\n").unwrap();
+        assert!(nightly.nightly);
+
+        let mut exercise = Exercise {
+            name: "asm1".into(),
+            requires_nightly: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            exercise.unmet_requirement(&stable).as_deref(),
+            Some("requires nightly")
+        );
+        assert_eq!(exercise.unmet_requirement(&nightly), None);
+
+        exercise.target = Some("x86_64-unknown-linux-gnu".into());
+        assert_eq!(
+            exercise.unmet_requirement(&nightly).as_deref(),
+            Some("requires target x86_64-unknown-linux-gnu")
+        );
+    }
+
+    #[test]
+    fn test_load_checks_prerequisites() {
+        let error = ExerciseList::load(Path::new("tests/fixture/order/prerequisite.toml"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "The exercise 'second' requires unknown exercises: zeroth"
+        );
+    }
+
+    #[test]
+    fn test_load_hint_requires_one_source() {
+        let error = ExerciseList::load(Path::new("tests/fixture/hint/invalid.toml"))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("'both_hints'"));
+    }
+
+    #[test]
+    fn test_run_args_test_threads() {
+        let mut exercise = Exercise {
+            name: "threaded".into(),
+            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
+            mode: Mode::Test,
+            ..Default::default()
+        };
+        let mut options = RunOptions::default();
+        assert_eq!(exercise.run_args(&options), ["--show-output"]);
+
+        exercise.test_threads = Some(1);
+        assert_eq!(
+            exercise.run_args(&options),
+            ["--show-output", "--test-threads=1"]
+        );
+
+        options.test_filter = Some("you_can_assert".into());
+        assert_eq!(
+            exercise.run_args(&options),
+            ["--show-output", "--test-threads=1", "you_can_assert"]
+        );
+    }
+
+    #[test]
+    fn test_require_ignored_tests() {
+        let mut exercise = Exercise {
+            name: "ignoredFailure".into(),
+            path: PathBuf::from("tests/fixture/failure/ignoredFailure.rs"),
+            mode: Mode::Test,
+            ..Default::default()
+        };
+        assert!(exercise
+            .compile(&RunOptions::default())
+            .unwrap()
+            .run()
+            .is_ok());
+
+        exercise.require_ignored = true;
+        assert_eq!(
+            exercise.run_args(&RunOptions::default()),
+            ["--show-output", "--include-ignored"]
+        );
+        let output = exercise
+            .compile(&RunOptions::default())
+            .unwrap()
+            .run()
+            .unwrap_err();
+        assert!(output.stdout.contains("test not_enabled_yet ... FAILED"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_stubborn_exercise() {
+        let exercise = Exercise {
+            name: "ignoresTerm".into(),
+            path: PathBuf::from("tests/fixture/failure/ignoresTerm.rs"),
+            mode: Mode::Compile,
+            timeout: Some(1),
+            ..Default::default()
+        };
+        let options = RunOptions {
+            kill_timeout: Some(1),
+            ..Default::default()
+        };
+        let compiled = exercise.compile(&options).unwrap();
+        let started = Instant::now();
+        let output = compiled.run().unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(output.signal, Some(libc::SIGKILL));
+        assert!(output.stdout.contains("Ignoring SIGTERM"));
+        assert!(output
+            .stderr
+            .contains("ignoresTerm was stopped after running for 1 seconds"));
+    }
+
+    #[test]
+    fn test_load_normalizes_paths() {
+        let list = ExerciseList::load(Path::new("tests/fixture/paths/info.toml")).unwrap();
+        let paths: Vec<_> = list.exercises.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("exercises/intro/intro1.rs"),
+                PathBuf::from("exercises/intro/intro2.rs"),
+                PathBuf::from("exercises/variables/variables1.rs"),
+            ]
+        );
+
+        let changed = Path::new("/home/learner/rustlings/exercises/intro/intro2.rs");
+        let matched: Vec<_> = list
+            .exercises
+            .iter()
+            .filter(|e| e.matches_path(changed))
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(matched, ["backslashes"]);
+    }
+
+    #[test]
+    fn test_solution_path() {
+        let mut exercise = Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/intro/intro1.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        assert_eq!(
+            exercise.solution_path(),
+            PathBuf::from("solutions/intro/intro1.rs")
+        );
+        exercise.path = PathBuf::from("intro1.rs");
+        assert_eq!(
+            exercise.solution_path(),
+            PathBuf::from("solutions/intro1.rs")
+        );
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        let exercise = Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/00_intro/intro1.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        assert!(exercise.matches_filter("0*_intro/*"));
+        assert!(exercise.matches_filter("exercises/*/intro?.rs"));
+        assert!(!exercise.matches_filter("exercises/*.rs"));
+        assert!(exercise.matches_filter("intro"));
+        assert!(exercise.matches_filter("00_intro/"));
+        assert!(!exercise.matches_filter("variables"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_matches_path_through_symlink() {
+        let root = std::env::temp_dir().join(format!("rustlings_symlink_{}", std::process::id()));
+        let real = root.join("mount");
+        fs::create_dir_all(&real).unwrap();
+        fs::write(real.join("intro1.rs"), "fn main() {}\n").unwrap();
+        let link = root.join("exercises");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let exercise = Exercise {
+            name: "intro1".into(),
+            path: link.join("intro1.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let event = real.join("intro1.rs").canonicalize().unwrap();
+        let other = Exercise {
+            name: "intro2".into(),
+            path: link.join("intro2.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let matched = (exercise.matches_path(&event), other.matches_path(&event));
+        let _ignored = fs::remove_dir_all(&root);
+        assert_eq!(matched, (true, false));
+    }
+
+    #[test]
+    fn test_load_min_rustlings_version() {
+        let list = ExerciseList::load(Path::new("tests/fixture/version/info.toml")).unwrap();
+        let required = Version::new(99, 0, 0);
+        assert_eq!(list.min_rustlings_version, Some(required.clone()));
+        assert_eq!(
+            list.required_version(&Version::new(5, 2, 1)),
+            Some(&required)
+        );
+        assert_eq!(list.required_version(&Version::new(99, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_load_resolves_editions() {
+        let list = ExerciseList::load(Path::new("tests/fixture/edition/info.toml")).unwrap();
+        let editions: Vec<_> = list
+            .exercises
+            .iter()
+            .map(|e| (e.name.as_str(), e.edition.as_deref()))
+            .collect();
+        assert_eq!(
+            editions,
+            [
+                ("old_keyword", Some("2015")),
+                ("new_prelude", Some("2021")),
+                ("overridden", Some("2015")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_duplicate_paths() {
+        let error = ExerciseList::load(Path::new("tests/fixture/paths/duplicate.toml"))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("share the file"));
+    }
+
     #[test]
     fn test_exercise_with_output() {
         let exercise = Exercise {
             name: "exercise_with_output".into(),
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
             mode: Mode::Test,
-            hint: String::new(),
+            ..Default::default()
         };
-        let out = exercise.compile().unwrap().run().unwrap();
+        let out = exercise
+            .compile(&RunOptions::default())
+            .unwrap()
+            .run()
+            .unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
 }
@@ -0,0 +1,141 @@
+use crate::data_gather::timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// A single verification attempt made during a watch session
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct HistoryEntry {
+    // Name of the attempted exercise
+    pub exercise: String,
+    // Seconds since the Unix epoch at which the attempt finished
+    pub timestamp: u64,
+    // Whether the exercise passed
+    pub passed: bool,
+}
+
+impl HistoryEntry {
+    pub fn now(exercise: &str, passed: bool) -> Self {
+        HistoryEntry {
+            exercise: exercise.to_string(),
+            timestamp: timestamp(),
+            passed,
+        }
+    }
+}
+
+// An append-only log of watch session attempts, one JSON object per line.
+// Writes are serialized so concurrent verifications can share one log.
+pub struct History {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl History {
+    pub fn new(path: PathBuf) -> Self {
+        History {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn push(&self, entry: &HistoryEntry) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+    }
+
+    // The last `count` entries of the log, oldest first
+    pub fn last(&self, count: usize) -> io::Result<Vec<HistoryEntry>> {
+        let _guard = self.lock.lock().unwrap();
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        let skip = entries.len().saturating_sub(count);
+        Ok(entries.split_off(skip))
+    }
+}
+
+// Format seconds since the Unix epoch as an ISO 8601 UTC date and time
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Convert days since the epoch to a civil date
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_history_round_trip() {
+        let path = std::env::temp_dir().join(format!("rustlings_history_{}", std::process::id()));
+        let _ignored = fs::remove_file(&path);
+        let history = History::new(path.clone());
+
+        let entries = vec![
+            HistoryEntry {
+                exercise: "intro1".into(),
+                timestamp: 1,
+                passed: true,
+            },
+            HistoryEntry {
+                exercise: "intro2".into(),
+                timestamp: 2,
+                passed: false,
+            },
+            HistoryEntry {
+                exercise: "intro2".into(),
+                timestamp: 3,
+                passed: true,
+            },
+        ];
+        for entry in &entries {
+            history.push(entry).unwrap();
+        }
+
+        assert_eq!(history.last(10).unwrap(), entries);
+        assert_eq!(history.last(2).unwrap(), entries[1..]);
+        let _ignored = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_668_000_000), "2022-11-09T13:20:00Z");
+    }
+}
@@ -0,0 +1,170 @@
+use crate::exercise::Exercise;
+use crate::verify::{ProgressObserver, VerifyFailed};
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// A progress event for front-ends wrapping rustlings,
+// sent as one JSON object per line
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    // An exercise is being verified
+    Started {
+        exercise: &'a str,
+        path: &'a Path,
+    },
+    // An exercise passed verification
+    Passed {
+        exercise: &'a str,
+    },
+    // An exercise failed verification
+    Failed {
+        exercise: &'a str,
+        reason: &'a VerifyFailed,
+    },
+    // The hint of the exercise that failed last
+    Hint {
+        exercise: &'a str,
+        hint: &'a str,
+    },
+}
+
+// The receiving end of the progress events, a Unix domain socket
+// or a named pipe given with `--ipc`
+pub struct Ipc {
+    // None once the front-end went away
+    stream: Mutex<Option<Box<dyn Write + Send>>>,
+}
+
+impl Ipc {
+    #[cfg(feature = "ipc")]
+    pub fn connect(path: &Path) -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            use std::os::unix::net::UnixStream;
+
+            if std::fs::metadata(path)?.file_type().is_socket() {
+                return Ok(Ipc::new(UnixStream::connect(path)?));
+            }
+        }
+        // A named pipe (or a FIFO on Unix) is written to like a file
+        let pipe = std::fs::OpenOptions::new().write(true).open(path)?;
+        Ok(Ipc::new(pipe))
+    }
+
+    #[cfg(not(feature = "ipc"))]
+    pub fn connect(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "rustlings was built without the `ipc` feature",
+        ))
+    }
+
+    #[cfg(feature = "ipc")]
+    fn new(stream: impl Write + Send + 'static) -> Self {
+        Ipc {
+            stream: Mutex::new(Some(Box::new(stream))),
+        }
+    }
+
+    // Send an event, a front-end that stopped listening is dropped
+    // instead of interrupting the exercises
+    pub fn send(&self, event: &Event) {
+        let mut stream = self.stream.lock().unwrap();
+        let Some(writer) = stream.as_mut() else {
+            return;
+        };
+        let mut line = serde_json::to_string(event).unwrap();
+        line.push('\n');
+        if writer
+            .write_all(line.as_bytes())
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            *stream = None;
+        }
+    }
+}
+
+impl ProgressObserver for Ipc {
+    fn on_start(&self, exercise: &Exercise) {
+        self.send(&Event::Started {
+            exercise: &exercise.name,
+            path: &exercise.path,
+        });
+    }
+
+    fn on_pass(&self, exercise: &Exercise, _elapsed: Duration) {
+        self.send(&Event::Passed {
+            exercise: &exercise.name,
+        });
+    }
+
+    // The hint follows the failure, for front-ends to show along with it
+    fn on_fail(&self, exercise: &Exercise, reason: &VerifyFailed) {
+        self.send(&Event::Failed {
+            exercise: &exercise.name,
+            reason,
+        });
+        if let Ok(hint) = exercise.read_hint() {
+            self.send(&Event::Hint {
+                exercise: &exercise.name,
+                hint: &hint,
+            });
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "ipc"))]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use crate::verify::FailedType;
+    use std::fs;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixListener;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_events_over_socket() {
+        let path = std::env::temp_dir().join(format!("rustlings_ipc_{}", std::process::id()));
+        let _ignored = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let ipc = Ipc::connect(&path).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let _ignored = fs::remove_file(&path);
+
+        let exercise = Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/intro/intro1.rs"),
+            mode: Mode::Compile,
+            hint: Some("Remove the marker".into()),
+            ..Default::default()
+        };
+        ipc.on_start(&exercise);
+        let reason = VerifyFailed::new(
+            FailedType::Compilation,
+            "error[E0308]: mismatched types".into(),
+        );
+        ipc.on_fail(&exercise, &reason);
+        drop(ipc);
+
+        let events: Vec<serde_json::Value> = BufReader::new(stream)
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["event"], "started");
+        assert_eq!(events[0]["path"], "exercises/intro/intro1.rs");
+        assert_eq!(events[1]["event"], "failed");
+        assert_eq!(events[1]["reason"]["kind"], "compilation");
+        assert_eq!(events[1]["reason"]["error_codes"][0], "E0308");
+        assert_eq!(events[2]["event"], "hint");
+        assert_eq!(events[2]["hint"], "Remove the marker");
+    }
+}
@@ -1,37 +1,76 @@
-use crate::data_gather::{DataGather, Record};
-use crate::exercise::{Exercise, ExerciseList};
-use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
-use crate::verify::verify;
+use crate::data_gather::{content_hash, DataGather, Effort};
+use crate::exercise::{
+    check_exercise, miri_available, order_reason, Compiler, Exercise, ExerciseList, ManifestFormat,
+    Mode, Panic, RunOptions, Toolchain,
+};
+use crate::history::{format_timestamp, History, HistoryEntry};
+use crate::ipc::Ipc;
+use crate::milestone::Milestones;
+use crate::project::{RustAnalyzerProject, SYSROOT_CACHE_PATH};
+use crate::review::ReviewEntry;
+use crate::run::{
+    bless, diagnostics, explain, on_pass, reset, reset_to_solution, run, warmup, Diagnostic,
+};
+use crate::ui::{
+    capture, print_finished, print_hint, print_json, print_welcome, strip_colors, write_stdout,
+};
+use crate::verify::{
+    annotate, bisect, chapters, check_quietly, passes, render_chapter, verify, verify_observed,
+    ChapterResult, FailedType, ReportFormat, Timings, VerifyFailed,
+};
 use argh::FromArgs;
-use console::Emoji;
+use console::{style, Term};
+use handlebars::Handlebars;
 use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use schemars::JsonSchema;
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use signal_hook::SigId;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use strip_ansi_escapes;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[macro_use]
 mod ui;
 
+mod bundle;
 mod data_gather;
 mod exercise;
+mod history;
+mod ipc;
+mod milestone;
+mod progress;
 mod project;
+mod review;
 mod run;
 mod verify;
 
 // In sync with crate version
 const VERSION: &str = "5.2.1";
 const DATA_PATH: &str = "data.jsonl";
+const HISTORY_PATH: &str = "history.jsonl";
+const REVIEW_PATH: &str = "review.jsonl";
+const MILESTONES_PATH: &str = ".rustlings-milestones.json";
+const PROGRESS_ARCHIVE_PATH: &str = "rustlings-progress.tar.gz";
 
 #[derive(FromArgs, PartialEq, Debug)]
 /// Rustlings is a collection of small exercises to get you used to writing and reading Rust code
@@ -42,6 +81,52 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// write JSON progress events of verify and watch to a Unix socket or named pipe
+    #[argh(option)]
+    ipc: Option<PathBuf>,
+    /// when to color the output: "always", "never" or "auto" (the default),
+    /// which colors it when writing to a terminal
+    #[argh(option, default = "ColorChoice::Auto")]
+    color: ColorChoice,
+    /// screen-reader-friendly output without ASCII art, emoji or progress
+    /// bars, also enabled by setting RUSTLINGS_ACCESSIBLE
+    #[argh(switch)]
+    plain: bool,
+    /// fail exercises that compile with warnings, unless they set allow_warnings
+    #[argh(switch)]
+    deny_warnings: bool,
+    /// compile the exercises outside of test mode with this panic strategy,
+    /// "abort" or "unwind", instead of their own
+    #[argh(option)]
+    panic: Option<Panic>,
+    /// stop exercises running for longer than this many seconds, unless
+    /// they set their own timeout
+    #[argh(option)]
+    timeout: Option<u64>,
+    /// the seconds a stopped exercise gets to exit before it is killed,
+    /// 5 by default
+    #[argh(option)]
+    kill_timeout: Option<u64>,
+    /// compile the exercises with this command instead of rustc, like
+    /// "sccache rustc", which is given the rustc arguments
+    #[argh(option)]
+    compiler: Option<Compiler>,
+    /// compile the exercises with this rustup toolchain, like "beta" or
+    /// "1.70", instead of the active one
+    #[argh(option)]
+    toolchain: Option<String>,
+    /// ignore the RUSTFLAGS and RUSTDOCFLAGS environment variables, so that
+    /// grading compiles the exercises the same way everywhere
+    #[argh(switch)]
+    no_inherit_flags: bool,
+    /// run against the exercises of a .tar.gz or .zip bundle instead of
+    /// the current directory
+    #[argh(option)]
+    bundle: Option<PathBuf>,
+    /// load info.toml with "toml" or info.yaml with "yaml", by default
+    /// info.toml unless there is only an info.yaml
+    #[argh(option)]
+    manifest_format: Option<ManifestFormat>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -54,24 +139,192 @@ enum Subcommands {
     Run(RunArgs),
     Reset(ResetArgs),
     Hint(HintArgs),
+    About(AboutArgs),
     List(ListArgs),
     Lsp(LspArgs),
+    History(HistoryArgs),
+    Stats(StatsArgs),
+    Warmup(WarmupArgs),
+    SeedData(SeedDataArgs),
+    ExportProgress(ExportProgressArgs),
+    ImportProgress(ImportProgressArgs),
     MyVerify(MyVerifyArgs),
+    CheckExercise(CheckExerciseArgs),
+    CheckStarter(CheckStarterArgs),
+    JsonSchema(JsonSchemaArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(switch)]
+    /// binary search for the first failing exercise, assuming the exercises
+    /// marked as done come first
+    bisect: bool,
+    #[argh(switch)]
+    /// print how long each exercise took to compile and run, slowest first
+    timings: bool,
+    #[argh(option)]
+    /// also write the timings as JSON to this file
+    timings_json: Option<PathBuf>,
+    #[argh(switch)]
+    /// print the exercises that would be verified, in order, without
+    /// compiling anything
+    dry_run: bool,
+    #[argh(option, default = "true")]
+    /// stop at the first failing exercise (the default) or, with
+    /// `--fail-fast false`, verify every exercise and list the failures
+    fail_fast: bool,
+    #[argh(option)]
+    /// only verify these exercises, a comma-separated list of exact names
+    only: Option<String>,
+    #[argh(option, default = "ReportFormat::Plain")]
+    /// with "github", also print compile errors as annotations when running
+    /// in GitHub Actions; "plain" (the default) prints the usual output only
+    format: ReportFormat,
+    #[argh(switch)]
+    /// also include the exercises marked as ignored
+    include_ignored: bool,
+    #[argh(switch)]
+    /// summarize each chapter whose exercises all pass in a single line,
+    /// only showing the details of the chapters with failures
+    group_by_chapter: bool,
+    #[argh(option)]
+    /// also write the output of each exercise, without colors, to
+    /// <name>.log in this directory
+    output_dir: Option<PathBuf>,
+    #[argh(switch)]
+    /// run the exercises under Miri, failing on undefined behavior
+    miri: bool,
+    #[argh(switch)]
+    /// hide the output of the exercises and only print how many passed,
+    /// best combined with `--fail-fast false`
+    summary_only: bool,
+    #[argh(option)]
+    /// only verify the i-th of n parts of the exercises, like 1/4, to
+    /// split the work between CI jobs
+    shard: Option<Shard>,
+    #[argh(switch)]
+    /// instead of stopping at a failing exercise, run it again whenever
+    /// it is saved and continue verifying the rest once it passes
+    watch_on_fail: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-exercise")]
+/// Checks the manifest entry and file of an exercise before submitting it
+struct CheckExerciseArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-starter")]
+/// Checks that the tests of the exercises weren't changed
+struct CheckStarterArgs {
+    #[argh(positional)]
+    /// only check this exercise
+    name: Option<String>,
+    #[argh(switch)]
+    /// print the hash of the tests to set as `starter_sha256` instead
+    print_hash: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "json-schema")]
+/// (maintenance) Prints the JSON schema of the results written by myverify
+struct JsonSchemaArgs {}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "myverify", description = "myverify")]
-struct MyVerifyArgs {}
+struct MyVerifyArgs {
+    /// apply a preset of options, currently only "grading"
+    #[argh(option)]
+    profile: Option<Profile>,
+    /// verify at most this many exercises at the same time
+    #[argh(option)]
+    jobs: Option<usize>,
+    /// exit with an error when an exercise fails
+    #[argh(switch)]
+    strict: bool,
+    /// don't exit with an error when an exercise fails, even with a
+    /// --profile that does
+    #[argh(switch)]
+    no_strict: bool,
+    /// also write the results as a JUnit XML report to this file
+    #[argh(option)]
+    junit: Option<PathBuf>,
+    /// where to write the JSON results (default: .github/result/check_result.json)
+    #[argh(option)]
+    output: Option<PathBuf>,
+    /// with "github", also print compile errors as annotations when running
+    /// in GitHub Actions; "plain" (the default) prints the usual output only
+    #[argh(option, default = "ReportFormat::Plain")]
+    format: ReportFormat,
+    /// exit with an error when less than this percentage of the exercises
+    /// pass, regardless of `--strict`
+    #[argh(option)]
+    fail_under: Option<f64>,
+    /// only verify the i-th of n parts of the exercises, like 1/4, to
+    /// split the work between CI jobs
+    #[argh(option)]
+    shard: Option<Shard>,
+    /// also render the results with this Handlebars template, given the
+    /// same data as the JSON results, to write a report of any format
+    #[argh(option)]
+    report_template: Option<PathBuf>,
+    /// where to write the report rendered with --report-template
+    #[argh(option)]
+    report_out: Option<PathBuf>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
 /// Reruns `verify` when files were edited
-struct WatchArgs {}
+struct WatchArgs {
+    #[argh(option)]
+    /// a shell command to run whenever a failing exercise passes, with the
+    /// exercise in the RUSTLINGS_EXERCISE and RUSTLINGS_EXERCISE_PATH variables
+    on_pass: Option<String>,
+    #[argh(option)]
+    /// the number of threads used to run the tests of test exercises
+    test_threads: Option<usize>,
+    #[argh(switch)]
+    /// verify the exercises once, stopping at the first failure, and exit
+    /// with a non-zero code if any exercise is left
+    once: bool,
+    #[argh(switch)]
+    /// keep the output of earlier verifications instead of clearing the
+    /// screen, setting each new one apart with a separator
+    no_clear: bool,
+    #[argh(switch)]
+    /// record every failed attempt in data.jsonl with its error and a hash
+    /// of the exercise, instead of only the distinct errors of solved exercises
+    keep_going: bool,
+    #[argh(option)]
+    /// scan the exercises for changes every this many milliseconds instead
+    /// of relying on file events, for filesystems like network mounts,
+    /// Docker volumes or WSL that don't report them
+    poll: Option<u64>,
+    #[argh(switch)]
+    /// also include the exercises marked as ignored
+    include_ignored: bool,
+    #[argh(option)]
+    /// also verify the current exercise again after this many seconds
+    /// without a verification, for filesystems whose changes are missed
+    /// even with --poll
+    watch_interval_verify: Option<u64>,
+    #[argh(option)]
+    /// keep this file updated with the current exercise, the progress and
+    /// a timestamp as JSON, for liveness probes of containers
+    status_file: Option<PathBuf>,
+    #[argh(option)]
+    /// another file or directory to watch, like a helper module shared by
+    /// the exercises, verifying every exercise again when it changes
+    watch_extra: Vec<PathBuf>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "run")]
@@ -80,6 +333,34 @@ struct RunArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(switch)]
+    /// explain the first compiler error code if compilation fails
+    explain: bool,
+    #[argh(option)]
+    /// the number of threads used to run the tests of test exercises
+    test_threads: Option<usize>,
+    #[argh(switch)]
+    /// print the result as JSON instead of the exercise's output
+    json: bool,
+    #[argh(switch)]
+    /// like --json, with the compiler diagnostics including their spans and suggestions
+    json_diagnostics: bool,
+    #[argh(switch)]
+    /// write the output of an output mode exercise to its
+    /// golden file, which verifying it compares with from then on
+    bless: bool,
+    #[argh(switch)]
+    /// run the exercise again whenever it is saved, until it passes
+    watch: bool,
+    #[argh(option)]
+    /// only run the tests of a test exercise whose name contains this
+    test: Option<String>,
+    #[argh(switch)]
+    /// run the exercise under Miri, failing on undefined behavior
+    miri: bool,
+    #[argh(switch)]
+    /// only compile the tests of a test exercise, without running them
+    no_run: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -89,6 +370,14 @@ struct ResetArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(option, default = "ResetTarget::Starter")]
+    /// what to reset the exercise to: "starter" (the default), or
+    /// "solution" to copy its reference solution over it for answer keys
+    to: ResetTarget,
+    #[argh(switch)]
+    /// with `--to solution`, overwrite the exercise even when its changes
+    /// can't be stashed because git doesn't track it
+    force: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -98,12 +387,85 @@ struct HintArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(switch)]
+    /// print the hint as JSON
+    json: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "about")]
+/// Describes what the given exercise teaches
+struct AboutArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
-struct LspArgs {}
+struct LspArgs {
+    #[argh(switch)]
+    /// determine the toolchain again instead of using the cached one
+    refresh: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "history")]
+/// Shows the exercises attempted in past watch sessions
+struct HistoryArgs {
+    #[argh(option, short = 'n', default = "10")]
+    /// the number of most recent attempts to show
+    count: usize,
+    #[argh(switch)]
+    /// print the attempts as JSON
+    json: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "stats")]
+/// Shows how many hints were used for each exercise
+struct StatsArgs {
+    #[argh(switch)]
+    /// show the failed attempts and time spent on each topic instead
+    per_topic: bool,
+    #[argh(switch)]
+    /// print the statistics as JSON
+    json: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "warmup")]
+/// Compiles a trivial program so the first exercise compiles faster
+struct WarmupArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "seed-data")]
+/// (maintenance) Records the solutions of the exercises solved so far in
+/// the gathered data, skipping exercises that already have records
+struct SeedDataArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "export-progress")]
+/// Saves the solved exercises to a .tar.gz archive, to continue on
+/// another machine with import-progress
+struct ExportProgressArgs {
+    #[argh(positional, default = "PathBuf::from(PROGRESS_ARCHIVE_PATH)")]
+    /// the archive to write, rustlings-progress.tar.gz by default
+    archive: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "import-progress")]
+/// Restores the solved exercises saved by export-progress
+struct ImportProgressArgs {
+    #[argh(positional, default = "PathBuf::from(PROGRESS_ARCHIVE_PATH)")]
+    /// the archive to read, rustlings-progress.tar.gz by default
+    archive: PathBuf,
+    #[argh(switch)]
+    /// also overwrite the exercises already solved here
+    force: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
@@ -116,8 +478,8 @@ struct ListArgs {
     /// show only the names of the exercises
     names: bool,
     #[argh(option, short = 'f')]
-    /// provide a string to match exercise names
-    /// comma separated patterns are acceptable
+    /// provide a string to match exercise names or paths, or a glob
+    /// (like `intro/*`) to match paths; comma separated patterns are acceptable
     filter: Option<String>,
     #[argh(switch, short = 'u')]
     /// display only exercises not yet solved
@@ -125,314 +487,1955 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(switch)]
+    /// print the exercises as JSON
+    json: bool,
+    #[argh(option)]
+    /// order the exercises by the given key instead of the recommended
+    /// order, "mtime" lists the most recently modified first
+    sort: Option<ListSort>,
+    #[argh(option)]
+    /// display only exercises with this tag, can be repeated to
+    /// display exercises with any of the tags
+    tag: Vec<String>,
+    #[argh(switch)]
+    /// print only the name (or path, with --paths) of the next exercise
+    /// to solve, nothing if all are done
+    next: bool,
+    #[argh(switch)]
+    /// also include the exercises marked as ignored
+    include_ignored: bool,
+    #[argh(switch)]
+    /// explain why each exercise comes where it does and which exercises
+    /// to solve before it
+    explain_order: bool,
+}
+
+#[derive(PartialEq, Debug)]
+enum ListSort {
+    Mtime,
+}
+
+impl FromStr for ListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mtime" => Ok(ListSort::Mtime),
+            _ => Err(format!("unknown sort key '{s}', expected \"mtime\"")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum ColorChoice {
+    Always,
+    Auto,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(ColorChoice::Always),
+            "auto" => Ok(ColorChoice::Auto),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "unknown color choice '{s}', expected \"always\", \"auto\" or \"never\""
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum ResetTarget {
+    Starter,
+    Solution,
+}
+
+impl FromStr for ResetTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "starter" => Ok(ResetTarget::Starter),
+            "solution" => Ok(ResetTarget::Solution),
+            _ => Err(format!(
+                "unknown reset target '{s}', expected \"starter\" or \"solution\""
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum Profile {
+    Grading,
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grading" => Ok(Profile::Grading),
+            _ => Err(format!("unknown profile '{s}', expected \"grading\"")),
+        }
+    }
+}
+
+// One of the `n` parts the exercises are split into with `--shard i/n`,
+// counting from 1
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Shard {
+    index: usize,
+    count: usize,
+}
+
+impl Shard {
+    // The exercises of this shard: every n-th one, so that every shard
+    // gets exercises of every chapter
+    fn select<T>(self, items: Vec<T>) -> Vec<T> {
+        items
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % self.count == self.index - 1)
+            .map(|(_, item)| item)
+            .collect()
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid shard '{s}', expected <i>/<n> like 1/4");
+        let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+        let index = index.parse().map_err(|_| invalid())?;
+        let count = count.parse().map_err(|_| invalid())?;
+        if index == 0 || index > count {
+            return Err(format!(
+                "invalid shard '{s}', the shard must be between 1 and {count}"
+            ));
+        }
+        Ok(Shard { index, count })
+    }
+}
+
+const CHECK_RESULT_PATH: &str = ".github/result/check_result.json";
+
+// The options of a `myverify` run once the profile and the explicit
+// flags are combined
+#[derive(PartialEq, Debug)]
+struct MyVerifyConfig {
+    jobs: Option<usize>,
+    strict: bool,
+    junit: Option<PathBuf>,
+    output: PathBuf,
+    fail_under: Option<f64>,
+}
+
+impl Default for MyVerifyConfig {
+    fn default() -> Self {
+        MyVerifyConfig {
+            jobs: None,
+            strict: false,
+            junit: None,
+            output: PathBuf::from(CHECK_RESULT_PATH),
+            fail_under: None,
+        }
+    }
+}
+
+impl MyVerifyConfig {
+    // The options the grading workflow always passes together
+    fn grading() -> Self {
+        MyVerifyConfig {
+            jobs: std::thread::available_parallelism().ok().map(usize::from),
+            strict: true,
+            junit: Some(PathBuf::from(".github/result/junit.xml")),
+            output: PathBuf::from(CHECK_RESULT_PATH),
+            fail_under: None,
+        }
+    }
+
+    // Start from the profile, explicit flags take precedence
+    fn new(args: &MyVerifyArgs) -> Self {
+        let preset = match args.profile {
+            Some(Profile::Grading) => MyVerifyConfig::grading(),
+            None => MyVerifyConfig::default(),
+        };
+        MyVerifyConfig {
+            jobs: args.jobs.or(preset.jobs),
+            strict: match (args.strict, args.no_strict) {
+                (true, _) => true,
+                (false, true) => false,
+                (false, false) => preset.strict,
+            },
+            junit: args.junit.clone().or(preset.junit),
+            output: args.output.clone().unwrap_or(preset.output),
+            fail_under: args.fail_under.or(preset.fail_under),
+        }
+    }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Serialize)]
+struct ListEntry {
+    name: String,
+    path: String,
+    status: &'static str,
+    // Last modification of the exercise file, in seconds since the Unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modified: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct HintEntry<'a> {
+    name: &'a str,
+    hint: &'a str,
+    // Whether a reference solution exists for the exercise
+    has_solution: bool,
+}
+
+// How often an exercise's hint was shown, for `stats --json`
+#[derive(Serialize)]
+struct HintUsage<'a> {
+    exercise: &'a str,
+    hints_used: usize,
+}
+
+// The result of `run --json`
+#[derive(Serialize)]
+struct RunReport<'a> {
+    name: &'a str,
+    status: &'static str,
+    kind: Option<FailedType>,
+    // The compiler or program output explaining the failure, without colors
+    error: Option<String>,
+    exit_code: Option<i32>,
+    elapsed_ms: u64,
+    // Only with `--json-diagnostics`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnostics: Option<Vec<Diagnostic>>,
+}
+
+impl<'a> RunReport<'a> {
+    fn new(exercise: &'a Exercise, failed: Option<&VerifyFailed>, elapsed: Duration) -> Self {
+        RunReport {
+            name: &exercise.name,
+            status: if failed.is_some() { "failed" } else { "passed" },
+            kind: failed.map(|failed| failed.kind),
+            error: failed.map(|failed| strip_colors(&failed.msg)),
+            exit_code: failed.and_then(|failed| failed.exit_code),
+            elapsed_ms: elapsed.as_millis() as u64,
+            diagnostics: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct ExerciseCheckList {
     pub exercises: Vec<ExerciseResult>,
     pub user_name: Option<String>,
     pub statistics: ExerciseStatistics,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct ExerciseResult {
     pub name: String,
     pub result: bool,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct ExerciseStatistics {
     pub total_exercations: usize,
     pub total_succeeds: usize,
     pub total_failures: usize,
+    // Points earned by the passed exercises
+    #[serde(default)]
+    pub score: u32,
+    // Points earned if every exercise passes
+    #[serde(default)]
+    pub max_score: u32,
+}
+
+impl ExerciseStatistics {
+    fn new(exercises: &[Exercise]) -> Self {
+        ExerciseStatistics {
+            total_exercations: exercises.len(),
+            total_succeeds: 0,
+            total_failures: 0,
+            score: 0,
+            max_score: exercises.iter().map(Exercise::points).sum(),
+        }
+    }
+
+    // Count the result of an exercise worth `points`
+    fn record(&mut self, points: u32, passed: bool) {
+        if passed {
+            self.total_succeeds += 1;
+            self.score += points;
+        } else {
+            self.total_failures += 1;
+        }
+    }
+
+    // The share of passed exercises, in percent
+    fn pass_percentage(&self) -> f64 {
+        if self.total_exercations == 0 {
+            return 100.0;
+        }
+        self.total_succeeds as f64 * 100.0 / self.total_exercations as f64
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    let args: Args = argh::from_env();
+    let args: Args = parse_args();
+    match args.color {
+        ColorChoice::Always => {
+            console::set_colors_enabled(true);
+            console::set_colors_enabled_stderr(true);
+        }
+        ColorChoice::Never => {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+            ui::set_no_color(true);
+        }
+        ColorChoice::Auto => {}
+    }
+    ui::set_plain(args.plain);
 
     if args.version {
-        println!("v{VERSION}");
+        outln!("v{VERSION}");
         std::process::exit(0);
     }
 
-    if args.nested.is_none() {
-        println!("\n{WELCOME}\n");
+    if let Some(Subcommands::JsonSchema(_)) = &args.nested {
+        let schema = schemars::schema_for!(ExerciseCheckList);
+        print_json(&schema);
+        return;
+    }
+
+    if let Some(bundle) = &args.bundle {
+        std::process::exit(run_bundle(bundle));
     }
 
-    if !Path::new("info.toml").exists() {
-        println!(
+    let manifest = manifest_path(args.manifest_format);
+    if !Path::new(manifest).exists() {
+        outln!(
             "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
+            env::current_exe().unwrap().to_str().unwrap()
         );
-        println!("Try `cd rustlings/`!");
+        outln!("Try `cd rustlings/`!");
         std::process::exit(1);
     }
 
     if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
+        outln!("We cannot find `rustc`.");
+        outln!("Try running `rustc --version` to diagnose your problem.");
+        outln!("For instructions on how to install Rust, check the README.");
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let mut exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    // The exercise being checked may be one the manifest can't load yet
+    if let Some(Subcommands::CheckExercise(subargs)) = &args.nested {
+        match check_exercise(Path::new(manifest), &subargs.name) {
+            Ok(problems) if problems.is_empty() => {
+                success!("{} looks ready to submit", subargs.name);
+            }
+            Ok(problems) => {
+                outln!("Found {} problems with {}:", problems.len(), subargs.name);
+                for problem in problems {
+                    outln!("  - {problem}");
+                }
+                std::process::exit(1);
+            }
+            Err(e) => {
+                outln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let list = load_exercises(manifest);
+    let mut exercises = list.exercises;
+    for exercise in &mut exercises {
+        exercise.timeout = exercise.timeout.or(args.timeout);
+    }
+    if let Some(toolchain) = &args.toolchain {
+        check_toolchain(toolchain);
+    }
+    let compiler = args
+        .compiler
+        .clone()
+        .unwrap_or_default()
+        .with_toolchain(args.toolchain.clone());
+    let compiler = if args.no_inherit_flags {
+        compiler
+    } else {
+        compiler.with_flags(env_flags("RUSTFLAGS"), env_flags("RUSTDOCFLAGS"))
+    };
+    let mut options = RunOptions {
+        compiler,
+        deny_warnings: args.deny_warnings,
+        kill_timeout: args.kill_timeout,
+        ..RunOptions::default()
+    };
+    if let Some(panic) = args.panic {
+        // The test harness needs to unwind on stable compilers
+        for exercise in exercises
+            .iter_mut()
+            .filter(|e| !matches!(e.mode, Mode::Test))
+        {
+            exercise.panic = Some(panic);
+        }
+    }
     let verbose = args.nocapture;
-    println!("args: {:?}", args);
+    let ipc = args.ipc.as_deref().map(|path| {
+        Ipc::connect(path).unwrap_or_else(|e| {
+            outln!("Failed to connect to {}: {e}", path.display());
+            std::process::exit(1)
+        })
+    });
 
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        print_welcome(list.welcome_message.as_deref());
+        let message = list.default_message.as_deref().unwrap_or(DEFAULT_OUT);
+        outln!("{message}\n");
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+            skip_ignored(&mut exercises, subargs.include_ignored);
+            if subargs.explain_order {
+                for exercise in &exercises {
+                    outln!(
+                        "{:<17}\t{}",
+                        exercise.name,
+                        order_reason(&list.order, exercise)
+                    );
+                }
+                std::process::exit(0);
+            }
+            if subargs.next {
+                if let Some(exercise) = next_exercise(&exercises) {
+                    if subargs.paths {
+                        outln!("{}", exercise.path.display());
+                    } else {
+                        outln!("{}", exercise.name);
+                    }
+                }
+                std::process::exit(0);
+            }
+            if !subargs.paths && !subargs.names && !subargs.json {
+                outln!(
+                    "{:<17}\t{:<46}\t{:<7}\t{:<20}",
+                    "Name",
+                    "Path",
+                    "Status",
+                    "Modified"
+                );
             }
+            // Each row is printed as soon as its exercise was read, only
+            // sorting by modification time has to stat them all first
+            let listed: Box<dyn Iterator<Item = (&Exercise, Option<u64>)>> = match subargs.sort {
+                Some(ListSort::Mtime) => {
+                    let mut listed: Vec<_> = exercises.iter().map(|e| (e, e.modified())).collect();
+                    // Most recently modified first, unreadable files last
+                    listed.sort_by(|(_, a), (_, b)| b.cmp(a));
+                    Box::new(listed.into_iter())
+                }
+                None => Box::new(exercises.iter().map(|e| (e, e.modified()))),
+            };
             let mut exercises_done: u16 = 0;
+            let mut exercises_total: u16 = 0;
+            let mut entries = Vec::new();
             let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
+            for (e, modified) in listed {
                 let fname = format!("{}", e.path.display());
                 let filter_cond = filters
                     .split(',')
                     .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
+                    .any(|f| e.matches_filter(f));
+                let done = e.looks_done();
+                exercises_total += 1;
+                let status = if done {
                     exercises_done += 1;
                     "Done"
                 } else {
                     "Pending"
                 };
                 let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
+                    (done && subargs.solved)
+                        || (!done && subargs.unsolved)
                         || (!subargs.solved && !subargs.unsolved)
                 };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
+                let tag_cond =
+                    subargs.tag.is_empty() || e.tags.iter().any(|t| subargs.tag.contains(t));
+                if solve_cond && tag_cond && (filter_cond || subargs.filter.is_none()) {
+                    if subargs.json {
+                        entries.push(ListEntry {
+                            name: e.name.clone(),
+                            path: fname,
+                            status,
+                            modified,
+                        });
+                        continue;
+                    }
                     let line = if subargs.paths {
                         format!("{fname}\n")
                     } else if subargs.names {
                         format!("{}\n", e.name)
                     } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
+                        let modified = modified.map(format_timestamp).unwrap_or_default();
+                        format!("{:<17}\t{fname:<46}\t{status:<7}\t{modified}\n", e.name)
                     };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
+                    write_stdout(&line);
                 }
-            });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
+            }
+            if subargs.json {
+                print_json(&entries);
+                std::process::exit(0);
+            }
+            let percentage_progress = exercises_done as f32 / exercises_total as f32 * 100.0;
+            outln!(
                 "Progress: You completed {} / {} exercises ({:.1} %).",
                 exercises_done,
-                exercises.len(),
+                exercises_total,
                 percentage_progress
             );
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
+            set_test_threads(&mut exercises, subargs.test_threads);
+            options.test_filter = subargs.test.clone();
+            options.no_run = subargs.no_run;
+            if subargs.miri {
+                use_miri(&mut options);
+            }
             let exercise = find_exercise(&subargs.name, &exercises);
+            if subargs.test.is_some() && !matches!(exercise.mode, Mode::Test) {
+                outln!(
+                    "{} has no tests to pick from, it isn't a test exercise",
+                    exercise.name
+                );
+                std::process::exit(1);
+            }
 
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            if subargs.watch {
+                match run_watch(exercise, &options, verbose) {
+                    Ok(WatchStatus::Finished) => {}
+                    Ok(WatchStatus::Unfinished) => std::process::exit(1),
+                    Err(e) => {
+                        outln!("Error: Could not watch {exercise}: {e:?}");
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
+            if subargs.bless {
+                bless(exercise, &options).unwrap_or_else(|_| std::process::exit(1));
+                return;
+            }
+            if subargs.json || subargs.json_diagnostics {
+                let started = Instant::now();
+                let result = check_quietly(exercise, &options);
+                let mut report = RunReport::new(exercise, result.as_ref().err(), started.elapsed());
+                if subargs.json_diagnostics {
+                    report.diagnostics =
+                        Some(diagnostics(exercise, &options).unwrap_or_else(|e| {
+                            outln!(
+                                "Failed to collect the diagnostics of {}: {e}",
+                                exercise.name
+                            );
+                            std::process::exit(1)
+                        }));
+                }
+                print_json(&report);
+                std::process::exit(if result.is_ok() { 0 } else { 1 });
+            }
+            run(exercise, &options, verbose).unwrap_or_else(|failed| {
+                if subargs.explain {
+                    explain(&failed);
+                }
+                std::process::exit(1)
+            });
         }
 
         Subcommands::Reset(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            let reset = match subargs.to {
+                ResetTarget::Starter => reset(exercise),
+                ResetTarget::Solution => reset_to_solution(exercise, subargs.force),
+            };
+            reset.unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Hint(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            println!("{}", exercise.hint);
+            let hint = exercise.read_hint().unwrap_or_else(|e| {
+                outln!("Failed to read the hint of {}: {e}", exercise.name);
+                std::process::exit(1)
+            });
+            let data_gather = DataGather::new(Path::new(DATA_PATH).to_path_buf());
+            if let Err(e) = data_gather.hint_viewed(&exercise.path) {
+                warn!("Failed to record the hint view: {}", e);
+            }
+            if subargs.json {
+                let entry = HintEntry {
+                    name: &exercise.name,
+                    hint: &hint,
+                    has_solution: exercise.solution_path().exists(),
+                };
+                print_json(&entry);
+            } else {
+                print_hint(&hint);
+            }
         }
 
-        Subcommands::Verify(_subargs) => {
-            let num_exercise = exercises.len();
-            for exercise in exercises {
-                match verify(&exercise, (0, num_exercise), verbose) {
-                    Err(_) => std::process::exit(1),
-                    Ok(_) => {}
+        Subcommands::About(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            let book_link = exercise.book_link();
+            if exercise.description.is_none()
+                && exercise.learning_goals.is_empty()
+                && book_link.is_none()
+            {
+                outln!(
+                    "{} has no description yet, `rustlings hint {}` helps when you are stuck.",
+                    exercise.name,
+                    exercise.name
+                );
+                return;
+            }
+            outln!("About {}:", exercise.name);
+            if let Some(description) = &exercise.description {
+                outln!();
+                outln!("{}", description.trim());
+            }
+            if !exercise.learning_goals.is_empty() {
+                outln!();
+                outln!("You will learn:");
+                for goal in &exercise.learning_goals {
+                    outln!("  - {goal}");
                 }
             }
-            // success
+            if let Some(link) = book_link {
+                outln!();
+                outln!("Read more in the Rust book: {link}");
+            }
         }
 
-        Subcommands::MyVerify(_subargs) => {
-            let toml_str = &fs::read_to_string("check.toml").unwrap();
-            exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-            let now_start = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            let rights = Arc::new(Mutex::new(0));
-            let alls = exercises.len();
-
-            let exercise_check_list = Arc::new(Mutex::new(ExerciseCheckList {
-                exercises: vec![],
-                user_name: None,
-                statistics: ExerciseStatistics {
-                    total_exercations: alls,
-                    total_succeeds: 0,
-                    total_failures: 0,
-                },
-            }));
-
-            let mut tasks = vec![];
-            for exercise in exercises {
-                let inner_exercise = exercise;
-                let c_mutex = Arc::clone(&rights);
-                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn(async move {
-                    match run(&inner_exercise, true) {
-                        Ok(_) => {
-                            *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now()
-                                .duration_since(UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs();
-                            println!("当前修改试卷总耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(
-                                ExerciseResult {
-                                    name: inner_exercise.name,
-                                    result: true,
-                                },
-                            );
-                            exercise_check_list_ref
-                                .lock()
-                                .unwrap()
-                                .statistics
-                                .total_succeeds += 1;
-                        }
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now()
-                                .duration_since(UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(
-                                ExerciseResult {
-                                    name: inner_exercise.name,
-                                    result: false,
-                                },
-                            );
-                            exercise_check_list_ref
-                                .lock()
-                                .unwrap()
-                                .statistics
-                                .total_failures += 1;
-                        }
-                    }
+        Subcommands::Verify(subargs) => {
+            skip_ignored(&mut exercises, subargs.include_ignored);
+            for note in skip_unsupported(&mut exercises, &options.compiler) {
+                outln!("{note}");
+            }
+            if subargs.miri {
+                use_miri(&mut options);
+            }
+            if let Some(names) = &subargs.only {
+                exercises = only_exercises(exercises, names).unwrap_or_else(|unknown| {
+                    outln!("No exercise found for: {}", unknown.join(", "));
+                    std::process::exit(1)
                 });
-                tasks.push(t);
             }
-            for task in tasks {
-                task.await.unwrap();
+            if let Some(shard) = subargs.shard {
+                exercises = shard.select(exercises);
             }
-            let serialized =
-                serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
-            fs::write(".github/result/check_result.json", serialized).unwrap();
-        }
+            if subargs.dry_run {
+                outln!("Would verify {} exercises:", exercises.len());
+                for exercise in &exercises {
+                    outln!("{:<17}\t{}", exercise.name, exercise.path.display());
+                }
+                std::process::exit(0);
+            }
+            if subargs.bisect {
+                let bisection = bisect(&exercises, Exercise::looks_done, |exercise| {
+                    passes(exercise, &options)
+                });
+                if bisection.linear {
+                    outln!("The exercises marked as done aren't the first ones, checked them in order instead.");
+                }
+                outln!(
+                    "Compiled {} of {} exercises.",
+                    bisection.compilations,
+                    exercises.len()
+                );
+                match bisection.first_failure {
+                    Some(index) => {
+                        outln!("The first failing exercise is {}:", exercises[index].name);
+                        let _ = verify(
+                            &exercises[index],
+                            &options,
+                            (index, exercises.len()),
+                            verbose,
+                        );
+                        std::process::exit(1)
+                    }
+                    None => {
+                        outln!("All exercises pass!");
+                        std::process::exit(0)
+                    }
+                }
+            }
+            if subargs.watch_on_fail && (subargs.group_by_chapter || subargs.summary_only) {
+                outln!("--watch-on-fail shows the output of the failing exercise, it can't be used with --group-by-chapter or --summary-only");
+                std::process::exit(1);
+            }
+            let num_exercise = exercises.len();
+            let started = Instant::now();
+            // The failed exercises fixed with `--watch-on-fail`
+            let mut fixed = Vec::new();
+            let mut timings = Timings::default();
+            let mut failures = Vec::new();
+            if let Some(dir) = &subargs.output_dir {
+                if let Err(e) = fs::create_dir_all(dir) {
+                    outln!("Failed to create {}: {e}", dir.display());
+                    std::process::exit(1);
+                }
+            }
+            let mut verify_timed = |exercise: &Exercise| {
+                let exercise_started = Instant::now();
+                let verify_exercise = || {
+                    verify_observed(
+                        exercise,
+                        &options,
+                        (0, num_exercise),
+                        verbose,
+                        &ipc.as_ref(),
+                    )
+                };
+                let result = match &subargs.output_dir {
+                    Some(dir) => {
+                        let (result, output) = capture(verify_exercise);
+                        write_stdout(&output);
+                        let log = dir.join(format!("{}.log", exercise.name));
+                        let stripped = strip_ansi_escapes::strip(&output).unwrap_or_default();
+                        if let Err(e) = fs::write(&log, stripped) {
+                            warn!("Failed to write {}", format!("{}: {e}", log.display()));
+                        }
+                        result
+                    }
+                    None => verify_exercise(),
+                };
+                timings.push(&exercise.name, exercise_started.elapsed());
+                if let Err(failed) = &result {
+                    annotate(subargs.format, &failed.reason);
+                    failures.push(exercise.name.clone());
+                }
+                result.is_ok()
+            };
+            if subargs.group_by_chapter && !subargs.summary_only {
+                'chapters: for (chapter, chapter_exercises) in chapters(&exercises) {
+                    let mut results = Vec::new();
+                    for exercise in chapter_exercises {
+                        let (passed, output) = capture(|| verify_timed(exercise));
+                        results.push(ChapterResult {
+                            name: &exercise.name,
+                            passed,
+                            output,
+                        });
+                        if !passed && subargs.fail_fast {
+                            write_stdout(&render_chapter(&chapter, &results));
+                            break 'chapters;
+                        }
+                    }
+                    write_stdout(&render_chapter(&chapter, &results));
+                }
+            } else {
+                for exercise in &exercises {
+                    let passed = if subargs.summary_only {
+                        capture(|| verify_timed(exercise)).0
+                    } else {
+                        verify_timed(exercise)
+                    };
+                    if !passed && subargs.watch_on_fail {
+                        outln!("Watching {exercise} until it passes, then verifying the rest.");
+                        match run_watch(exercise, &options, verbose) {
+                            Ok(WatchStatus::Finished) => fixed.push(exercise.name.clone()),
+                            Ok(WatchStatus::Unfinished) => std::process::exit(1),
+                            Err(e) => {
+                                outln!("Error: Could not watch {exercise}: {e:?}");
+                                std::process::exit(1);
+                            }
+                        }
+                    } else if !passed && subargs.fail_fast {
+                        break;
+                    }
+                }
+            }
+            failures.retain(|name| !fixed.contains(name));
+
+            timings.total = started.elapsed().as_secs_f64();
+            if subargs.timings {
+                timings.print();
+            }
+            if let Some(path) = &subargs.timings_json {
+                let json = serde_json::to_string_pretty(&timings).unwrap();
+                if let Err(e) = fs::write(path, json) {
+                    warn!("Failed to write the timings: {}", e);
+                }
+            }
+            if subargs.summary_only {
+                let verified = timings.exercises.len();
+                outln!("{}/{verified} passed", verified - failures.len());
+                if !failures.is_empty() {
+                    std::process::exit(1)
+                }
+            }
+            if !failures.is_empty() {
+                outln!(
+                    "{} of {} verified exercises failed: {}",
+                    failures.len(),
+                    timings.exercises.len(),
+                    failures.join(", ")
+                );
+                std::process::exit(1)
+            }
+        }
+
+        Subcommands::MyVerify(subargs) => {
+            if subargs.strict && subargs.no_strict {
+                outln!("--strict and --no-strict can't be given together");
+                std::process::exit(1);
+            }
+            let config = MyVerifyConfig::new(&subargs);
+            // A broken template is reported before verifying anything
+            let report = match (&subargs.report_template, &subargs.report_out) {
+                (Some(template), Some(out)) => {
+                    match fs::read_to_string(template)
+                        .map_err(|e| e.to_string())
+                        .and_then(|source| report_renderer(&source))
+                    {
+                        Ok(renderer) => Some((renderer, out)),
+                        Err(e) => {
+                            outln!("Invalid report template {}: {e}", template.display());
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                (None, None) => None,
+                _ => {
+                    outln!("--report-template and --report-out have to be given together");
+                    std::process::exit(1);
+                }
+            };
+            exercises = load_exercises("check.toml").exercises;
+            if let Some(shard) = subargs.shard {
+                exercises = shard.select(exercises);
+            }
+            let now_start = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            let rights = Arc::new(Mutex::new(0));
+            let alls = exercises.len();
+
+            let exercise_check_list = Arc::new(Mutex::new(ExerciseCheckList {
+                exercises: vec![],
+                user_name: None,
+                statistics: ExerciseStatistics::new(&exercises),
+            }));
+
+            let order: Vec<String> = exercises.iter().map(|e| e.name.clone()).collect();
+            let jobs = config.jobs.unwrap_or(alls).max(1);
+            let permits = Arc::new(tokio::sync::Semaphore::new(jobs));
+            let mut tasks = vec![];
+            for exercise in exercises {
+                let inner_exercise = exercise;
+                let c_mutex = Arc::clone(&rights);
+                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
+                let format = subargs.format;
+                let _verbose = verbose;
+                let options = options.clone();
+                // Running an exercise blocks its thread until it exits, so it
+                // runs on the blocking threads, as many at a time as permitted
+                let permit = Arc::clone(&permits).acquire_owned().await.unwrap();
+                let t = tokio::task::spawn_blocking(move || {
+                    let _permit = permit;
+                    match run(&inner_exercise, &options, true) {
+                        Ok(_) => {
+                            *c_mutex.lock().unwrap() += 1;
+                            outln!("{}执行成功", inner_exercise.name);
+                            outln!("总的题目数: {}", alls);
+                            outln!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                            let now_end = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs();
+                            outln!("当前修改试卷总耗时: {} s", now_end - now_start);
+                            let mut check_list = exercise_check_list_ref.lock().unwrap();
+                            check_list.statistics.record(inner_exercise.points(), true);
+                            check_list.exercises.push(ExerciseResult {
+                                name: inner_exercise.name,
+                                result: true,
+                            });
+                        }
+                        Err(failed) => {
+                            annotate(format, &failed);
+                            outln!("{}执行失败", inner_exercise.name);
+                            outln!("总的题目数: {}", alls);
+                            outln!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                            let now_end = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs();
+                            outln!("当前修改试卷耗时: {} s", now_end - now_start);
+                            let mut check_list = exercise_check_list_ref.lock().unwrap();
+                            check_list.statistics.record(inner_exercise.points(), false);
+                            check_list.exercises.push(ExerciseResult {
+                                name: inner_exercise.name,
+                                result: false,
+                            });
+                        }
+                    }
+                });
+                tasks.push(t);
+            }
+            for task in tasks {
+                task.await.unwrap();
+            }
+            let mut check_list = exercise_check_list.lock().unwrap();
+            sort_results(&mut check_list.exercises, &order);
+            let serialized = serde_json::to_string_pretty(&*check_list).unwrap();
+            fs::write(&config.output, serialized).unwrap();
+            if let Some(junit) = &config.junit {
+                if let Err(e) = fs::write(junit, junit_report(&check_list.exercises)) {
+                    outln!(
+                        "Failed to write the JUnit report to {}: {e}",
+                        junit.display()
+                    );
+                    std::process::exit(1);
+                }
+            }
+            if let Some((renderer, out)) = &report {
+                let rendered = renderer
+                    .render(REPORT_TEMPLATE, &*check_list)
+                    .map_err(|e| e.to_string())
+                    .and_then(|report| fs::write(out, report).map_err(|e| e.to_string()));
+                if let Err(e) = rendered {
+                    outln!("Failed to write the report to {}: {e}", out.display());
+                    std::process::exit(1);
+                }
+            }
+            if let Some(threshold) = config.fail_under {
+                let percentage = check_list.statistics.pass_percentage();
+                if percentage < threshold {
+                    outln!(
+                        "Passed {percentage:.1}% of the exercises, below the required {threshold}%"
+                    );
+                    std::process::exit(1);
+                }
+                outln!(
+                    "Passed {percentage:.1}% of the exercises, meeting the required {threshold}%"
+                );
+            }
+            if config.strict && check_list.exercises.iter().any(|e| !e.result) {
+                std::process::exit(1);
+            }
+        }
 
-        Subcommands::Lsp(_subargs) => {
+        // Handled before loading the exercises
+        Subcommands::CheckExercise(_subargs) => {}
+
+        Subcommands::JsonSchema(_) => unreachable!("printed before loading the exercises"),
+
+        Subcommands::CheckStarter(subargs) => {
+            let checked = match &subargs.name {
+                Some(name) => vec![find_exercise(name, &exercises)],
+                None => exercises.iter().collect(),
+            };
+            if subargs.print_hash {
+                for exercise in checked {
+                    match exercise.test_block_sha256() {
+                        Ok(Some(hash)) => outln!("{:<17}\t{hash}", exercise.name),
+                        Ok(None) => outln!("{:<17}\thas no tests", exercise.name),
+                        Err(e) => outln!("{:<17}\tcan't be read: {e}", exercise.name),
+                    }
+                }
+                return;
+            }
+            let mut intact = 0;
+            let mut altered = 0;
+            for exercise in checked {
+                match exercise.tests_altered() {
+                    Ok(Some(false)) => intact += 1,
+                    Ok(Some(true)) => {
+                        altered += 1;
+                        warn!("The tests of {} were changed!", exercise);
+                        outln!(
+                            "Run `rustlings reset {}` to restore them, this resets your code too.",
+                            exercise.name
+                        );
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        altered += 1;
+                        warn!("Failed to read {}", format!("{exercise}: {e}"));
+                    }
+                }
+            }
+            if altered > 0 {
+                std::process::exit(1);
+            }
+            outln!("The tests of {intact} exercises are intact.");
+        }
+
+        Subcommands::SeedData(_subargs) => {
+            let solved: Vec<&Path> = exercises
+                .iter()
+                .filter(|e| e.looks_done())
+                .map(|e| e.path.as_path())
+                .collect();
+            let data_gather = DataGather::new(Path::new(DATA_PATH).to_path_buf());
+            match data_gather.backfill(&solved) {
+                Ok(written) => outln!("Recorded {written} solved exercises in {DATA_PATH}."),
+                Err(e) => {
+                    outln!("Failed to record the solved exercises: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::ExportProgress(subargs) => {
+            match progress::export(&exercises, &subargs.archive) {
+                Ok(exported) => outln!(
+                    "Saved {exported} solved exercises to {}.",
+                    subargs.archive.display()
+                ),
+                Err(e) => {
+                    outln!("Failed to save the progress: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::ImportProgress(subargs) => {
+            let imported = progress::import(&exercises, &subargs.archive, subargs.force)
+                .unwrap_or_else(|e| {
+                    outln!("Failed to restore the progress: {e}");
+                    std::process::exit(1)
+                });
+            for name in &imported.conflicts {
+                warn!(
+                    "{}",
+                    format!("{name} is solved differently here, kept this solution")
+                );
+            }
+            for path in &imported.unknown {
+                outln!("Skipped {}, it isn't an exercise here.", path.display());
+            }
+            outln!(
+                "Restored {} solved exercises from {}.",
+                imported.restored.len(),
+                subargs.archive.display()
+            );
+            if !imported.conflicts.is_empty() {
+                outln!("Run again with --force to overwrite the solutions made here.");
+            }
+        }
+
+        Subcommands::History(subargs) => {
+            let history = History::new(Path::new(HISTORY_PATH).to_path_buf());
+            let entries = history.last(subargs.count).unwrap_or_else(|e| {
+                outln!("Failed to read the watch history: {e}");
+                std::process::exit(1)
+            });
+            if subargs.json {
+                print_json(&entries);
+                return;
+            }
+            if entries.is_empty() {
+                outln!("No attempts recorded yet. Run `rustlings watch` to get started!");
+            }
+            for entry in entries {
+                let status = if entry.passed { "Passed" } else { "Failed" };
+                outln!(
+                    "{}\t{:<17}\t{status}",
+                    format_timestamp(entry.timestamp),
+                    entry.exercise
+                );
+            }
+        }
+
+        Subcommands::Stats(subargs) => {
+            let data_gather = DataGather::new(Path::new(DATA_PATH).to_path_buf());
+            if subargs.per_topic {
+                let efforts = data_gather.efforts().unwrap_or_else(|e| {
+                    outln!("Failed to read {DATA_PATH}: {e}");
+                    std::process::exit(1)
+                });
+                let topics = topic_efforts(&exercises, &efforts);
+                if subargs.json {
+                    print_json(&topics);
+                    return;
+                }
+                if topics.is_empty() {
+                    outln!("No attempts recorded yet.");
+                }
+                for topic in topics {
+                    outln!(
+                        "{:<17}\t{} attempts\t{:.1} per exercise\t{}",
+                        topic.topic,
+                        topic.effort.attempts,
+                        topic.effort.attempts as f64 / topic.exercises as f64,
+                        format_duration(topic.effort.seconds)
+                    );
+                }
+                return;
+            }
+            let views = data_gather.hint_views().unwrap_or_else(|e| {
+                outln!("Failed to read {DATA_PATH}: {e}");
+                std::process::exit(1)
+            });
+            let usage: Vec<HintUsage> = exercises
+                .iter()
+                .filter_map(|exercise| {
+                    let hints_used = *views.get(&exercise.path.display().to_string())?;
+                    Some(HintUsage {
+                        exercise: &exercise.name,
+                        hints_used,
+                    })
+                })
+                .collect();
+            if subargs.json {
+                print_json(&usage);
+                return;
+            }
+            if views.is_empty() {
+                outln!("No hints used yet.");
+            }
+            for entry in usage {
+                match entry.hints_used {
+                    1 => outln!("{:<17}\t1 hint used", entry.exercise),
+                    count => outln!("{:<17}\t{count} hints used", entry.exercise),
+                }
+            }
+        }
+
+        Subcommands::Warmup(_subargs) => match warmup(&options.compiler) {
+            Ok(true) => outln!("The compiler is warmed up, happy hacking!"),
+            Ok(false) => outln!("The compiler is already warm."),
+            Err(e) => {
+                outln!("Failed to warm up the compiler: {e}");
+                std::process::exit(1);
+            }
+        },
+
+        Subcommands::Lsp(subargs) => {
             let mut project = RustAnalyzerProject::new();
             project
-                .get_sysroot_src()
+                .get_sysroot_src_cached(Path::new(SYSROOT_CACHE_PATH), subargs.refresh)
                 .expect("Couldn't find toolchain path, do you have `rustc` installed?");
             project
                 .exercies_to_json()
                 .expect("Couldn't parse rustlings exercises files");
 
-            if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
-            } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
-            } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+            if project.crates.is_empty() {
+                outln!("Failed find any exercises, make sure you're in the `rustlings` folder");
+            } else if project.write_to_disk().is_err() {
+                outln!("Failed to write rust-project.json to disk for rust-analyzer");
+            } else {
+                outln!("Successfully generated rust-project.json");
+                outln!("rust-analyzer will now parse exercises, restart your language server or editor")
+            }
+        }
+
+        Subcommands::Watch(subargs) => {
+            // `--once` doesn't watch anything
+            let exercises_dir = Path::new("exercises");
+            if !subargs.once && !exercises_dir.is_dir() {
+                let path = env::current_dir()
+                    .map(|dir| dir.join(exercises_dir))
+                    .unwrap_or_else(|_| exercises_dir.to_path_buf());
+                outln!(
+                    "exercises directory not found at {}; are you in the rustlings folder?",
+                    path.display()
+                );
+                std::process::exit(1);
+            }
+            skip_ignored(&mut exercises, subargs.include_ignored);
+            let skipped = skip_unsupported(&mut exercises, &options.compiler);
+            set_test_threads(&mut exercises, subargs.test_threads);
+            let counts = list
+                .milestones
+                .clone()
+                .unwrap_or_else(|| milestone::DEFAULT_COUNTS.to_vec());
+            let milestones = Milestones::load(PathBuf::from(MILESTONES_PATH), counts)
+                .map_err(|e| warn!("Failed to load the milestones: {}", e))
+                .ok();
+            let input = ShellInput::default();
+            match watch(
+                &exercises,
+                &options,
+                verbose,
+                &subargs,
+                ipc.as_ref(),
+                milestones,
+                &skipped,
+                &input,
+            ) {
+                Err(e) => {
+                    outln!(
+                        "Error: Could not watch your progress. Error message was {:?}.",
+                        e
+                    );
+                    outln!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    print_finished(list.final_message.as_deref());
+                    offer_certificate(&exercises, &input);
+                }
+                Ok(WatchStatus::Unfinished) if subargs.once => std::process::exit(1),
+                Ok(WatchStatus::Unfinished) => {
+                    outln!("We hope you're enjoying learning about Rust!");
+                    outln!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+                }
+            }
+        }
+    }
+}
+
+fn spawn_watch_shell(
+    failed_exercise: &Arc<Mutex<Option<FailedExercise>>>,
+    should_quit: Arc<AtomicBool>,
+    events: Sender<DebouncedEvent>,
+    data_gather: Arc<DataGather>,
+    shell_input: ShellInput,
+) {
+    let failed_exercise = Arc::clone(failed_exercise);
+    shell_input.running.store(true, Ordering::SeqCst);
+    outln!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+    thread::spawn(move || {
+        let mut editor = match Editor::<WatchHelper, DefaultHistory>::new() {
+            Ok(editor) => editor,
+            Err(error) => {
+                outln!("error reading commands: {error}");
+                shell_input.running.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+        editor.set_helper(Some(WatchHelper));
+        // There is no history yet the first time
+        let _ = editor.load_history(WATCH_HISTORY_PATH);
+        loop {
+            match editor.readline("") {
+                Ok(input) => {
+                    if let Some(answer) = shell_input.answer.lock().unwrap().take() {
+                        let _ = answer.send(input);
+                        continue;
+                    }
+                    let input = input.trim();
+                    if !input.is_empty() && editor.add_history_entry(input).unwrap_or(false) {
+                        let _ = editor.append_history(WATCH_HISTORY_PATH);
+                    }
+                    watch_command(input, &failed_exercise, &should_quit, &events, &data_gather)
+                }
+                // Ctrl-C while typing, which would otherwise interrupt watch mode
+                Err(ReadlineError::Interrupted) => watch_command(
+                    "quit",
+                    &failed_exercise,
+                    &should_quit,
+                    &events,
+                    &data_gather,
+                ),
+                // stdin was closed, there are no more commands to read
+                Err(ReadlineError::Eof) => {
+                    shell_input.running.store(false, Ordering::SeqCst);
+                    shell_input.answer.lock().unwrap().take();
+                    break;
+                }
+                Err(error) => outln!("error reading command: {error}"),
+            }
+        }
+    });
+}
+
+// The commands of the watch shell, completed with Tab
+const WATCH_COMMANDS: [&str; 7] = ["hint", "open", "clear", "retry", "report", "quit", "help"];
+// The commands typed in watch mode, recalled with the arrow keys
+const WATCH_HISTORY_PATH: &str = ".rustlings-watch-history";
+
+// The watch shell commands starting with what was typed
+fn command_candidates(typed: &str) -> Vec<String> {
+    let typed = typed.trim_start();
+    WATCH_COMMANDS
+        .iter()
+        .filter(|command| command.starts_with(typed))
+        .map(|command| command.to_string())
+        .collect()
+}
+
+// Line editing of the watch shell, only completing the commands
+struct WatchHelper;
+
+impl Completer for WatchHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let typed = &line[..pos];
+        let start = typed.len() - typed.trim_start().len();
+        Ok((start, command_candidates(typed)))
+    }
+}
+
+impl Hinter for WatchHelper {
+    type Hint = String;
+}
+
+impl Highlighter for WatchHelper {}
+
+impl Validator for WatchHelper {}
+
+impl Helper for WatchHelper {}
+
+// Shared with the watch shell, which keeps reading stdin once it started,
+// so that questions asked meanwhile get the next line instead of the shell
+#[derive(Clone, Default)]
+struct ShellInput {
+    // Whether the watch shell reads stdin
+    running: Arc<AtomicBool>,
+    // Where the shell sends the next line instead of running it
+    answer: Arc<Mutex<Option<Sender<String>>>>,
+}
+
+impl ShellInput {
+    // Read the answer to a question from stdin, None once it is closed
+    fn read_answer(&self) -> Option<String> {
+        if self.running.load(Ordering::SeqCst) {
+            let (tx, rx) = channel();
+            *self.answer.lock().unwrap() = Some(tx);
+            return rx.recv().ok();
+        }
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(input),
+        }
+    }
+}
+
+const CERTIFICATE_PATH: &str = "rustlings-certificate";
+
+// A summary of the finished exercises to share
+#[derive(Serialize, PartialEq, Debug)]
+struct Certificate {
+    exercises_solved: usize,
+    // The failed attempts along the way
+    attempts: usize,
+    seconds: u64,
+    topics: Vec<TopicEffort>,
+}
+
+impl Certificate {
+    fn new(exercises: &[Exercise], efforts: &HashMap<String, Effort>) -> Self {
+        let topics = topic_efforts(exercises, efforts);
+        Certificate {
+            exercises_solved: exercises.len(),
+            attempts: topics.iter().map(|t| t.effort.attempts).sum(),
+            seconds: topics.iter().map(|t| t.effort.seconds).sum(),
+            topics,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Rustlings completion certificate\n\n\
+             Solved all {} exercises in {}, with {} failed attempts along the way.\n",
+            self.exercises_solved,
+            format_duration(self.seconds),
+            self.attempts
+        );
+        if !self.topics.is_empty() {
+            markdown.push_str("\n| Topic | Exercises | Attempts | Time |\n|---|---|---|---|\n");
+            for topic in &self.topics {
+                markdown.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    topic.topic,
+                    topic.exercises,
+                    topic.effort.attempts,
+                    format_duration(topic.effort.seconds)
+                ));
+            }
+        }
+        markdown
+    }
+}
+
+// Once every exercise is done, offer to save a certificate built from
+// data.jsonl as Markdown and JSON. Skipped without a terminal to ask in.
+fn offer_certificate(exercises: &[Exercise], input: &ShellInput) {
+    if !Term::stdout().is_term() || !io::stdin().is_terminal() {
+        return;
+    }
+    outln!("Save a completion certificate to share your achievement? [y/N]");
+    let answer = input
+        .read_answer()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    if answer != "y" && answer != "yes" {
+        return;
+    }
+    let efforts = DataGather::new(Path::new(DATA_PATH).to_path_buf())
+        .efforts()
+        .unwrap_or_else(|e| {
+            warn!("Failed to read the statistics for the certificate: {}", e);
+            HashMap::new()
+        });
+    let certificate = Certificate::new(exercises, &efforts);
+    let markdown = format!("{CERTIFICATE_PATH}.md");
+    let json = format!("{CERTIFICATE_PATH}.json");
+    let written = fs::write(&markdown, certificate.to_markdown())
+        .and_then(|_| fs::write(&json, serde_json::to_string_pretty(&certificate).unwrap()));
+    match written {
+        Ok(()) => success!(
+            "Saved your certificate to {}",
+            format!("{markdown} and {json}")
+        ),
+        Err(e) => warn!("Failed to save the certificate: {}", e),
+    }
+}
+
+// A duration like "12m 05s"
+fn format_duration(seconds: u64) -> String {
+    format!("{}m {:02}s", seconds / 60, seconds % 60)
+}
+
+// Reports the changes to the exercises, through the file events of the
+// platform or, on filesystems without them, by polling. The watchers stop
+// when dropped.
+enum ExercisesWatcher {
+    Events { watcher: RecommendedWatcher },
+    Poll { watcher: PollWatcher },
+}
+
+impl ExercisesWatcher {
+    // Watch `dir`, scanning it every `poll` milliseconds if given
+    fn new(dir: &Path, tx: Sender<DebouncedEvent>, poll: Option<u64>) -> notify::Result<Self> {
+        let mut watcher = match poll {
+            Some(interval) => ExercisesWatcher::Poll {
+                watcher: PollWatcher::new(tx, Duration::from_millis(interval))?,
+            },
+            None => ExercisesWatcher::Events {
+                watcher: Watcher::new(tx, Duration::from_secs(2))?,
+            },
+        };
+        watcher.watch(dir)?;
+        Ok(watcher)
+    }
+
+    // Also report the changes to `path`
+    fn watch(&mut self, path: &Path) -> notify::Result<()> {
+        match self {
+            ExercisesWatcher::Events { watcher } => watcher.watch(path, RecursiveMode::Recursive),
+            ExercisesWatcher::Poll { watcher } => watcher.watch(path, RecursiveMode::Recursive),
+        }
+    }
+}
+
+// Whether `path` is one of the extra watched paths or inside of one
+fn is_extra_path(extra: &[PathBuf], path: &Path) -> bool {
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    extra
+        .iter()
+        .filter_map(|extra| extra.canonicalize().ok())
+        .any(|extra| path.starts_with(extra))
+}
+
+// The exercise whose file the event reports as deleted, if any
+fn removed_exercise<'a>(exercises: &'a [Exercise], event: &DebouncedEvent) -> Option<&'a Exercise> {
+    match event {
+        DebouncedEvent::Remove(path) if !path.exists() => {
+            exercises.iter().find(|e| e.matches_path(path))
+        }
+        _ => None,
+    }
+}
+
+// Parse the command line like `argh::from_env`, but also accept
+// options written as `--name=value`
+fn parse_args() -> Args {
+    let strings: Vec<String> = env::args().collect();
+    let command = Path::new(&strings[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&strings[0]);
+    let args = split_option_values(&strings[1..]);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    Args::from_args(&[command], &args).unwrap_or_else(|early_exit| {
+        std::process::exit(match early_exit.status {
+            Ok(()) => {
+                outln!("{}", early_exit.output);
+                0
+            }
+            Err(()) => {
+                eprintln!("{}", early_exit.output);
+                1
+            }
+        })
+    })
+}
+
+// Extract the bundle into a temporary directory and run the same command
+// against it, removing the directory afterwards whichever way it exits.
+// Returns the exit code of the command.
+fn run_bundle(bundle: &Path) -> i32 {
+    let dir = env::temp_dir().join(format!("rustlings_bundle_{}", std::process::id()));
+    let root = match bundle::extract(bundle, &dir) {
+        Ok(root) => root,
+        Err(e) => {
+            outln!("Failed to extract the bundle: {e}");
+            let _ = fs::remove_dir_all(&dir);
+            return 1;
+        }
+    };
+
+    // Ctrl-C reaches the command too, wait for it to stop before cleaning up
+    let _ = signal_hook::flag::register(
+        signal_hook::consts::SIGINT,
+        Arc::new(AtomicBool::new(false)),
+    );
+    let args: Vec<String> = env::args().skip(1).collect();
+    let status = env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(without_bundle(&split_option_values(&args)))
+            .current_dir(&root)
+            .status()
+    });
+    let _ = fs::remove_dir_all(&dir);
+    match status {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            outln!("Failed to run rustlings on the bundle: {e}");
+            1
+        }
+    }
+}
+
+// The arguments without the `--bundle` option and its value
+fn without_bundle(args: &[String]) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            kept.push(arg.clone());
+            kept.extend(args.cloned());
+            break;
+        }
+        if arg == "--bundle" {
+            args.next();
+            continue;
+        }
+        kept.push(arg.clone());
+    }
+    kept
+}
+
+// Split every `--name=value` before a `--` into `--name` and `value`.
+// argh only accepts the latter, this lets every subcommand take both.
+fn split_option_values(args: &[String]) -> Vec<String> {
+    let mut split = Vec::new();
+    let mut options_ended = false;
+    for arg in args {
+        options_ended |= arg == "--";
+        match arg.split_once('=') {
+            Some((name, value)) if !options_ended && name.starts_with("--") => {
+                split.push(name.to_string());
+                split.push(value.to_string());
+            }
+            _ => split.push(arg.clone()),
+        }
+    }
+    split
+}
+
+// Moves the cursor home and clears to the end of the screen, so the
+// scrollback is kept
+const CLEAR_SCREEN: &str = "\x1b[1;1H\x1b[0J";
+const SCREEN_SEPARATOR: &str = "\n====================\n\n";
+
+// Clear the visible part of the terminal. Output that isn't a terminal is
+// left untouched.
+fn clear_screen() {
+    new_screen(false);
+}
+
+// Set the output of the next verification apart from the previous one
+fn new_screen(no_clear: bool) {
+    write_stdout(screen_break(no_clear, Term::stdout().is_term()));
+}
+
+// With `--no-clear` the earlier output is kept and followed by a separator,
+// otherwise a terminal is cleared
+fn screen_break(no_clear: bool, is_term: bool) -> &'static str {
+    match (no_clear, is_term) {
+        (true, _) => SCREEN_SEPARATOR,
+        (false, true) => CLEAR_SCREEN,
+        (false, false) => "",
+    }
+}
+
+// Put the results, pushed as the concurrent verifications finish, back
+// in the order of the exercises so that runs can be compared
+fn sort_results(results: &mut [ExerciseResult], order: &[String]) {
+    results.sort_by_key(|result| {
+        order
+            .iter()
+            .position(|name| *name == result.name)
+            .unwrap_or(order.len())
+    });
+}
+
+// A JUnit XML report of the `myverify` results, as read by CI systems
+fn junit_report(results: &[ExerciseResult]) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let failures = results.iter().filter(|r| !r.result).count();
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report.push_str(&format!(
+        "<testsuite name=\"rustlings\" tests=\"{}\" failures=\"{failures}\">\n",
+        results.len()
+    ));
+    for result in results {
+        let name = escape(&result.name);
+        if result.result {
+            report.push_str(&format!("  <testcase name=\"{name}\"/>\n"));
+        } else {
+            report.push_str(&format!("  <testcase name=\"{name}\">\n"));
+            report.push_str(&format!("    <failure message=\"{name} failed\"/>\n"));
+            report.push_str("  </testcase>\n");
+        }
+    }
+    report.push_str("</testsuite>\n");
+    report
+}
+
+const REPORT_TEMPLATE: &str = "report";
+
+// The renderer of a `myverify --report-template`. Strict mode makes
+// misspelled fields an error instead of rendering them empty.
+fn report_renderer(template: &str) -> Result<Handlebars<'static>, String> {
+    let mut renderer = Handlebars::new();
+    renderer.set_strict_mode(true);
+    renderer
+        .register_template_string(REPORT_TEMPLATE, template)
+        .map_err(|e| e.to_string())?;
+    Ok(renderer)
+}
+
+// The exercise watch mode is stuck on, shared with the watch shell
+struct FailedExercise {
+    name: String,
+    hint: String,
+    path: PathBuf,
+}
+
+impl FailedExercise {
+    fn new(exercise: &Exercise) -> Self {
+        FailedExercise {
+            name: exercise.name.clone(),
+            hint: exercise
+                .read_hint()
+                .unwrap_or_else(|e| format!("Failed to read the hint of {}: {e}", exercise.name)),
+            path: exercise.path.clone(),
+        }
+    }
+}
+
+// How often an unedited exercise may flip between passing and failing
+// within `FLAKY_WINDOW` before watch mode stops re-verifying it
+const FLAKY_FLIPS: usize = 3;
+const FLAKY_WINDOW: Duration = Duration::from_secs(60);
+
+struct FlakyState {
+    content: u64,
+    passed: bool,
+    flips: Vec<Instant>,
+    paused: bool,
+}
+
+// Notices exercises whose result keeps changing while their file doesn't,
+// which usually means the tests are nondeterministic
+#[derive(Default)]
+struct FlakyDetector {
+    states: HashMap<String, FlakyState>,
+}
+
+impl FlakyDetector {
+    // Whether re-verifying the exercise is paused, until its content changes
+    fn is_paused(&self, name: &str, content: u64) -> bool {
+        self.states
+            .get(name)
+            .is_some_and(|state| state.paused && state.content == content)
+    }
+
+    // Record a verification result, returns true if the exercise just got paused
+    fn record(&mut self, name: &str, content: u64, passed: bool, now: Instant) -> bool {
+        let fresh = FlakyState {
+            content,
+            passed,
+            flips: Vec::new(),
+            paused: false,
+        };
+        let state = self.states.entry(name.to_string()).or_insert(fresh);
+        if state.content != content {
+            // An edit explains any change of result
+            state.content = content;
+            state.passed = passed;
+            state.flips.clear();
+            state.paused = false;
+            return false;
+        }
+        if state.passed != passed {
+            state.passed = passed;
+            state.flips.push(now);
+        }
+        state
+            .flips
+            .retain(|flip| now.duration_since(*flip) <= FLAKY_WINDOW);
+        if !state.paused && state.flips.len() > FLAKY_FLIPS {
+            state.paused = true;
+            return true;
+        }
+        false
+    }
+}
+
+// The command opening `path` in the editor, which may come with
+// arguments like "code --wait". None if there is no editor.
+fn open_command(editor: Option<&str>, path: &Path) -> Option<Command> {
+    let mut words = editor?.split_whitespace();
+    let mut command = Command::new(words.next()?);
+    command.args(words).arg(path);
+    Some(command)
+}
+
+// Run a single command typed into the watch mode shell
+fn watch_command(
+    input: &str,
+    failed_exercise: &Mutex<Option<FailedExercise>>,
+    should_quit: &AtomicBool,
+    events: &Sender<DebouncedEvent>,
+    data_gather: &DataGather,
+) {
+    if input == "hint" {
+        if let Some(failed) = &*failed_exercise.lock().unwrap() {
+            print_hint(&failed.hint);
+            if let Err(e) = data_gather.hint_viewed(&failed.path) {
+                warn!("Failed to record the hint view: {}", e);
+            }
+        }
+    } else if input == "open" {
+        if let Some(failed) = &*failed_exercise.lock().unwrap() {
+            let editor = env::var("VISUAL").or_else(|_| env::var("EDITOR")).ok();
+            match open_command(editor.as_deref(), &failed.path) {
+                Some(mut command) => {
+                    if let Err(e) = command.status() {
+                        outln!("Failed to open {}: {e}", failed.path.display());
+                    }
+                }
+                None => outln!(
+                    "Set $VISUAL or $EDITOR to open exercises, the current one is {}",
+                    failed.path.display()
+                ),
+            }
+        }
+    } else if input == "clear" {
+        clear_screen();
+    } else if input == "retry" {
+        // The watch loop re-verifies the current exercise on a rescan,
+        // which the watcher also asks for when it missed events
+        if events.send(DebouncedEvent::Rescan).is_err() {
+            outln!("watch mode is no longer running");
+        }
+    } else if let Some(message) = input
+        .strip_prefix("report")
+        .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    {
+        let message = message.trim();
+        match &*failed_exercise.lock().unwrap() {
+            _ if message.is_empty() => outln!("Describe what needs a review: report <message>"),
+            Some(failed) => {
+                let entry = ReviewEntry::now(&failed.name, message);
+                match review::append(Path::new(REVIEW_PATH), &entry) {
+                    Ok(()) => outln!("Reported {} in {REVIEW_PATH}, thanks!", failed.name),
+                    Err(e) => warn!("Failed to write the report: {}", e),
+                }
+            }
+            None => outln!("There is no current exercise to report"),
+        }
+    } else if input.eq("quit") {
+        should_quit.store(true, Ordering::SeqCst);
+        outln!("Bye!");
+    } else if input.eq("help") {
+        outln!("Commands available to you in watch mode:");
+        outln!("  hint             - prints the current exercise's hint");
+        outln!("  open             - opens the current exercise in $VISUAL or $EDITOR");
+        outln!("  clear            - clears the screen");
+        outln!("  retry            - verifies the current exercise again");
+        outln!("  report <message> - notes a problem with the current exercise in {REVIEW_PATH}");
+        outln!("  quit             - quits watch mode");
+        outln!("  help             - displays this help message");
+        outln!();
+        outln!("Watch mode automatically re-evaluates the current exercise");
+        outln!("when you edit a file's contents.")
+    } else {
+        outln!("unknown command: {input}");
+    }
+}
+
+fn load_exercises(manifest: &str) -> ExerciseList {
+    let list = ExerciseList::load(Path::new(manifest)).unwrap_or_else(|e| {
+        outln!("Failed to load the exercises from {manifest}: {e}");
+        std::process::exit(1)
+    });
+    let version = Version::parse(VERSION).unwrap();
+    // Printed on stderr, so the output of `--json` stays valid JSON
+    if let Some(required) = list.required_version(&version) {
+        eprintln!(
+            "{} {}",
+            style("!").red(),
+            style(format!(
+                "These exercises need rustlings v{required} or newer"
+            ))
+            .red()
+        );
+        eprintln!("You are running v{VERSION}, some exercises may fail to run or verify.");
+        eprintln!("To update, run `cargo install --force --path .` from the rustlings directory.");
+    }
+    list
+}
+
+// The effort recorded for the exercises of a topic
+#[derive(Serialize, PartialEq, Debug)]
+struct TopicEffort {
+    topic: String,
+    // The exercises of the topic with recorded data
+    exercises: usize,
+    effort: Effort,
+}
+
+// Sum up the recorded efforts, keyed by exercise path, by the topic of
+// the exercises, the ones that took the most attempts first. Exercises
+// without a topic are counted as "uncategorized".
+fn topic_efforts(exercises: &[Exercise], efforts: &HashMap<String, Effort>) -> Vec<TopicEffort> {
+    let mut topics: Vec<TopicEffort> = Vec::new();
+    for exercise in exercises {
+        let Some(effort) = efforts.get(&exercise.path.display().to_string()) else {
+            continue;
+        };
+        let name = exercise.topic.as_deref().unwrap_or("uncategorized");
+        let index = match topics.iter().position(|t| t.topic == name) {
+            Some(index) => index,
+            None => {
+                topics.push(TopicEffort {
+                    topic: name.to_string(),
+                    exercises: 0,
+                    effort: Effort::default(),
+                });
+                topics.len() - 1
             }
+        };
+        let topic = &mut topics[index];
+        topic.exercises += 1;
+        topic.effort.attempts += effort.attempts;
+        topic.effort.seconds += effort.seconds;
+    }
+    // A stable sort keeps tied topics in manifest order
+    topics.sort_by_key(|t| std::cmp::Reverse(t.effort.attempts));
+    topics
+}
+
+// The top-level manifest in the given format, or info.toml unless only
+// an info.yaml exists
+fn manifest_path(format: Option<ManifestFormat>) -> &'static str {
+    let format = format.unwrap_or_else(|| {
+        let yaml = ManifestFormat::Yaml.info_path();
+        if !Path::new(ManifestFormat::Toml.info_path()).exists() && Path::new(yaml).exists() {
+            ManifestFormat::Yaml
+        } else {
+            ManifestFormat::Toml
         }
+    });
+    format.info_path()
+}
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
-                std::process::exit(1);
-            }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
-            }
-        },
+// The exercise to solve next: the first one not marked as done
+fn next_exercise(exercises: &[Exercise]) -> Option<&Exercise> {
+    exercises.iter().find(|e| !e.looks_done())
+}
+
+// Leave out the exercises marked as ignored, unless they should be included
+fn skip_ignored(exercises: &mut Vec<Exercise>, include_ignored: bool) {
+    if !include_ignored {
+        exercises.retain(|e| !e.ignored);
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint  - prints the current exercise's hint");
-                    println!("  clear - clears the screen");
-                    println!("  quit  - quits watch mode");
-                    println!("  help  - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else {
-                    println!("unknown command: {input}");
-                }
-            }
-            Err(error) => println!("error reading command: {error}"),
+// Leave out the exercises the toolchain can't verify, like nightly-only
+// ones on stable, returning a note for each
+fn skip_unsupported(exercises: &mut Vec<Exercise>, compiler: &Compiler) -> Vec<String> {
+    if !exercises
+        .iter()
+        .any(|e| e.requires_nightly || e.target.is_some())
+    {
+        return Vec::new();
+    }
+    let toolchain = match Toolchain::detect(compiler) {
+        Ok(toolchain) => toolchain,
+        Err(e) => {
+            warn!("Failed to detect the toolchain: {}", e);
+            return Vec::new();
         }
+    };
+    let mut notes = Vec::new();
+    exercises.retain(|exercise| match exercise.unmet_requirement(&toolchain) {
+        Some(requirement) => {
+            notes.push(format!("{} skipped: {requirement}", exercise.name));
+            false
+        }
+        None => true,
     });
+    notes
+}
+
+// Run the exercises under Miri, exiting with instructions when it
+// isn't installed
+fn use_miri(options: &mut RunOptions) {
+    if !miri_available() {
+        outln!("Miri isn't available for the current toolchain.");
+        outln!("It needs a nightly toolchain: `rustup +nightly component add miri`, then run rustlings with RUSTUP_TOOLCHAIN=nightly.");
+        std::process::exit(1);
+    }
+    options.miri = true;
+}
+
+// Override the test harness threads of every exercise if requested
+fn set_test_threads(exercises: &mut [Exercise], test_threads: Option<usize>) {
+    if test_threads.is_some() {
+        for exercise in exercises {
+            exercise.test_threads = test_threads;
+        }
+    }
 }
 
 fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
@@ -441,8 +2444,13 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
             .iter()
             .find(|e| !e.looks_done())
             .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
+                if ui::no_emoji() {
+                    outln!("Congratulations! You have done all the exercises!");
+                    outln!("There are no more exercises to do next!");
+                } else {
+                    outln!("🎉 Congratulations! You have done all the exercises!");
+                    outln!("🔚 There are no more exercises to do next!");
+                }
                 std::process::exit(1)
             })
     } else {
@@ -450,131 +2458,420 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
             .iter()
             .find(|e| e.name == name)
             .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
+                outln!("No exercise found for '{name}'!");
+                if let Some(closest) = closest_name(name, exercises) {
+                    outln!("Did you mean '{closest}'?");
+                }
                 std::process::exit(1)
             })
     }
 }
 
+// The exercise name closest to the mistyped `name`, if one is close enough
+// to be a typo: at most a third of its characters differ
+fn closest_name<'a>(name: &str, exercises: &'a [Exercise]) -> Option<&'a str> {
+    let max_distance = name.chars().count().max(3) / 3;
+    exercises
+        .iter()
+        .map(|e| (levenshtein(name, &e.name), e.name.as_str()))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+// The number of single character insertions, deletions and substitutions
+// turning `a` into `b`
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// The exercises named in the comma-separated `names`, in the order of the
+// manifest, or all the names that match no exercise
+fn only_exercises(exercises: Vec<Exercise>, names: &str) -> Result<Vec<Exercise>, Vec<&str>> {
+    let names: Vec<&str> = names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    let unknown: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|name| !exercises.iter().any(|e| e.name == *name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(unknown);
+    }
+    Ok(exercises
+        .into_iter()
+        .filter(|e| names.contains(&e.name.as_str()))
+        .collect())
+}
+
 enum WatchStatus {
     Finished,
     Unfinished,
 }
 
-fn watch(exercises: &[Exercise], verbose: bool) -> notify::Result<WatchStatus> {
-    let data_gather = DataGather::new(Path::new(DATA_PATH).to_path_buf());
-    let mut record = Record::empty();
+// Warn when watching the exercises takes most of the inotify watches,
+// before watch mode fails or misses changes in the middle of a session
+#[cfg(target_os = "linux")]
+fn check_inotify_limit(dir: &Path) {
+    let limit = fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()
+        .and_then(|limit| limit.trim().parse().ok());
+    if let Some(warning) = limit.and_then(|limit| inotify_warning(count_directories(dir), limit)) {
+        warn!("{}", warning);
+    }
+}
 
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
+// inotify takes a watch per directory, out of a limit shared with every
+// other program of the user, like editors watching their projects
+#[cfg(target_os = "linux")]
+fn inotify_warning(needed: usize, limit: usize) -> Option<String> {
+    if needed * 5 < limit * 4 {
+        return None;
     }
+    Some(format!(
+        "Watching the exercises takes {needed} of the {limit} inotify watches available, \
+         watch mode may fail or miss changes.\n\
+         Raise the limit with `sudo sysctl fs.inotify.max_user_watches=524288`, \
+         or run `rustlings watch --poll 1000` instead."
+    ))
+}
+
+// The directory and the directories below it
+#[cfg(target_os = "linux")]
+fn count_directories(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    1 + entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| count_directories(&entry.path()))
+        .sum::<usize>()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn watch<'a>(
+    exercises: &'a [Exercise],
+    options: &RunOptions,
+    verbose: bool,
+    args: &WatchArgs,
+    ipc: Option<&Ipc>,
+    milestones: Option<Milestones>,
+    skipped: &[String],
+    input: &ShellInput,
+) -> notify::Result<WatchStatus> {
+    let data_gather = Arc::new(
+        DataGather::new(Path::new(DATA_PATH).to_path_buf()).with_attempts(args.keep_going),
+    );
+    let history = History::new(Path::new(HISTORY_PATH).to_path_buf());
+    let log_attempt = |exercise: &Exercise, passed: bool| {
+        if let Err(e) = history.push(&HistoryEntry::now(&exercise.name, passed)) {
+            warn!("Failed to write the watch history: {}", e);
+        }
+    };
+
+    // The exercises whose last verification failed, so that passing now is
+    // a transition to passing. Those of earlier sessions are in the history.
+    let failing = RefCell::new(
+        history
+            .last(usize::MAX)
+            .unwrap_or_default()
+            .into_iter()
+            .fold(HashSet::new(), |mut failing, entry| {
+                if entry.passed {
+                    failing.remove(&entry.exercise);
+                } else {
+                    failing.insert(entry.exercise);
+                }
+                failing
+            }),
+    );
+    // The exercises that passed their last verification, the solved ones
+    // for the milestones and for what is left to verify
+    let solved = RefCell::new(HashSet::new());
+    let note_pass = |exercise: &'a Exercise| {
+        solved.borrow_mut().insert(exercise.name.as_str());
+        if failing.borrow_mut().remove(&exercise.name) {
+            if let Some(command) = &args.on_pass {
+                on_pass(command, exercise);
+            }
+        }
+        data_gather.passed(&exercise.path);
+    };
+    let note_fail = |exercise: &Exercise| {
+        solved.borrow_mut().remove(exercise.name.as_str());
+        failing.borrow_mut().insert(exercise.name.clone());
+    };
+
+    let milestones = RefCell::new(milestones);
+    let celebrate = || {
+        if let Some(milestones) = milestones.borrow_mut().as_mut() {
+            let solved = solved.borrow();
+            match milestones.check(exercises, |e| solved.contains(e.name.as_str())) {
+                Ok(messages) => {
+                    for message in messages {
+                        success!("{}", message);
+                    }
+                }
+                Err(e) => warn!("Failed to record the milestones: {}", e),
+            }
+        }
+    };
 
     let (tx, rx) = channel();
     let should_quit = Arc::new(AtomicBool::new(false));
+    // Ctrl-C asks the loop to quit so it can stop between verifications
+    // rather than in the middle of writing collected data
+    signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&should_quit))?;
 
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(2))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
-
-    clear_screen();
+    // A single pass has no use for file events
+    let _watcher = if args.once {
+        None
+    } else {
+        #[cfg(target_os = "linux")]
+        if args.poll.is_none() {
+            check_inotify_limit(Path::new("./exercises"));
+        }
+        let mut watcher = ExercisesWatcher::new(Path::new("./exercises"), tx.clone(), args.poll)?;
+        for path in &args.watch_extra {
+            watcher.watch(path)?;
+        }
+        if !args.no_clear {
+            clear_screen();
+        }
+        Some(watcher)
+    };
+    for note in skipped {
+        outln!("{note}");
+    }
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let mut failed_exercise_hint = Arc::new(Mutex::default());
+    let mut failed_exercise = Arc::new(Mutex::default());
+    // The exercise that failed last, verified again by the `retry` command
+    let current_exercise = Cell::new(None);
+    let interval = args.watch_interval_verify.map(Duration::from_secs);
+    // When the last verification finished, event-driven or not
+    let last_verified = Cell::new(Instant::now());
+    let report_status = || {
+        if let Some(path) = &args.status_file {
+            let done = solved.borrow().len();
+            if let Err(e) = write_status_file(path, exercises, current_exercise.get(), done) {
+                warn!("Failed to write the status file: {}", e);
+            }
+        }
+    };
     let mut num_done = 0;
     for exercise in exercises.iter() {
-        record.reset_path(&exercise.path);
-
-        match verify(exercise, (num_done, exercises.len()), verbose) {
+        if should_quit.load(Ordering::SeqCst) {
+            return Ok(WatchStatus::Unfinished);
+        }
+        // Not an attempt of the learner, only where they left off
+        let result = verify_observed(
+            exercise,
+            options,
+            (num_done, exercises.len()),
+            verbose,
+            &ipc,
+        );
+        match result {
             Ok(_) => {
                 num_done += 1;
-                if record.check_file(&exercise.path) {
-                    record.read_right_code();
-                    data_gather.push(record.clone());
-                }
-                record.clear();
+                note_pass(exercise);
             }
             Err(exercise_failed) => {
-                record.set_error(
-                    &std::str::from_utf8(
-                        &strip_ansi_escapes::strip(&exercise_failed.reason.msg).unwrap(),
-                    )
-                    .unwrap()
-                    .to_string(),
-                );
-                failed_exercise_hint =
-                    Arc::new(Mutex::new(Some(to_owned_hint(exercise_failed.exercise))));
+                note_fail(exercise);
+                data_gather.failed(&exercise.path, &strip_colors(&exercise_failed.reason.msg));
+                failed_exercise = Arc::new(Mutex::new(Some(FailedExercise::new(
+                    exercise_failed.exercise,
+                ))));
+                current_exercise.set(Some(exercise_failed.exercise));
                 break;
             }
         };
     }
+    // Once for the exercises solved since the last session
+    celebrate();
+    report_status();
 
     if num_done == exercises.len() {
         // When all the exercises are done, we will reach here.
         return Ok(WatchStatus::Finished);
     }
 
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    if args.once {
+        if let Some(failed) = &*failed_exercise.lock().unwrap() {
+            outln!("Hint:");
+            print_hint(&failed.hint);
+        }
+        return Ok(WatchStatus::Unfinished);
+    }
+
+    spawn_watch_shell(
+        &failed_exercise,
+        Arc::clone(&should_quit),
+        tx,
+        Arc::clone(&data_gather),
+        input.clone(),
+    );
+    let flaky = RefCell::new(FlakyDetector::default());
+    // Verify the given exercises in order until one fails. Only the
+    // verifications the learner triggered are `record`ed as attempts.
+    let verify_pending = |pending_exercises: Vec<&'a Exercise>, num_done: usize, record: bool| {
+        for exercise in pending_exercises {
+            if should_quit.load(Ordering::SeqCst) {
+                break;
+            }
+            let content = content_hash(&exercise.path);
+            if flaky.borrow().is_paused(&exercise.name, content) {
+                outln!(
+                    "Not re-verifying {} until it is edited, its tests look flaky.",
+                    exercise
+                );
+                break;
+            }
+            let result = verify_observed(
+                exercise,
+                options,
+                (num_done, exercises.len()),
+                verbose,
+                &ipc,
+            );
+            if record {
+                log_attempt(exercise, result.is_ok());
+            }
+            if flaky
+                .borrow_mut()
+                .record(&exercise.name, content, result.is_ok(), Instant::now())
+            {
+                warn!(
+                    "{} keeps flipping between passing and failing without being edited, its tests may be flaky!",
+                    exercise
+                );
+                outln!("Watch mode won't verify it again until you edit it.");
+            }
+            match result {
+                Ok(_) => {
+                    note_pass(exercise);
+                    celebrate();
+                }
+                Err(exercise_failed) => {
+                    note_fail(exercise);
+                    let mut failed_exercise = failed_exercise.lock().unwrap();
+                    *failed_exercise = Some(FailedExercise::new(exercise_failed.exercise));
+                    current_exercise.set(Some(exercise_failed.exercise));
+                    if record {
+                        data_gather
+                            .failed(&exercise.path, &strip_colors(&exercise_failed.reason.msg));
+                    }
+                    break;
+                }
+            }
+        }
+        last_verified.set(Instant::now());
+        report_status();
+    };
+    // The exercise at `filepath` first, then the pending ones in order
+    let pending_from = |filepath: &Path| -> Vec<&'a Exercise> {
+        exercises
+            .iter()
+            .find(|e| e.matches_path(filepath))
+            .into_iter()
+            .chain(exercises.iter().filter(|e| {
+                !solved.borrow().contains(e.name.as_str()) && !e.matches_path(filepath)
+            }))
+            .collect()
+    };
+    // Verify the exercise at `filepath` first, then the pending ones in order
+    // until one fails. Returns true once every exercise is done.
+    let verify_changed = |filepath: &Path| {
+        let pending_exercises = pending_from(filepath);
+        let num_done = solved.borrow().len();
+        new_screen(args.no_clear);
+
+        verify_pending(pending_exercises, num_done, true);
+        // Success when all exercise are done.
+        solved.borrow().len() == exercises.len()
+    };
     loop {
         match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
+            Ok(event) => {
+                let finished = match &event {
+                    // Any exercise may depend on an extra path, so all of
+                    // them are verified again, the done ones included
+                    DebouncedEvent::Create(b)
+                    | DebouncedEvent::Chmod(b)
+                    | DebouncedEvent::Write(b)
+                        if is_extra_path(&args.watch_extra, b) =>
+                    {
+                        new_screen(args.no_clear);
+                        verify_pending(exercises.iter().collect(), 0, true);
+                        solved.borrow().len() == exercises.len()
+                    }
+                    DebouncedEvent::Create(b)
+                    | DebouncedEvent::Chmod(b)
+                    | DebouncedEvent::Write(b)
+                        if b.extension() == Some(OsStr::new("rs")) && b.exists() =>
+                    {
+                        verify_changed(&b.as_path().canonicalize().unwrap())
+                    }
+                    DebouncedEvent::Remove(_) => {
+                        if let Some(exercise) = removed_exercise(exercises, &event) {
+                            warn!("{} was deleted!", exercise);
+                            outln!(
+                                "Run `rustlings reset {}` to restore it, then continue where you left off.",
+                                exercise.name
                             );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-
-                        if num_done == exercises.len() {
-                            // Success when all exercise are done.
-                            return Ok(WatchStatus::Finished);
-                        }
-
-                        for exercise in pending_exercises {
-                            record.reset_path(&exercise.path);
-                            match verify(exercise, (num_done, exercises.len()), verbose) {
-                                Ok(_) => {
-                                    // record data
-                                    if record.check_file(&exercise.path) {
-                                        record.read_right_code();
-                                        data_gather.push(record.clone());
-                                    }
-                                    record.clear();
-                                }
-                                Err(exercise_failed) => {
-                                    let mut failed_exercise_hint =
-                                        failed_exercise_hint.lock().unwrap();
-                                    *failed_exercise_hint =
-                                        Some(to_owned_hint(exercise_failed.exercise));
-                                    // record failure msg
-                                    record.set_error(
-                                        &std::str::from_utf8(
-                                            &strip_ansi_escapes::strip(&exercise_failed.reason.msg)
-                                                .unwrap(),
-                                        )
-                                        .unwrap()
-                                        .to_string(),
-                                    );
-                                    break;
-                                }
-                            }
                         }
+                        false
                     }
+                    DebouncedEvent::Rescan => match current_exercise.get() {
+                        Some(current) => verify_changed(&current.path),
+                        None => false,
+                    },
+                    _ => false,
+                };
+                if finished {
+                    return Ok(WatchStatus::Finished);
                 }
-                _ => {}
-            },
+            }
             Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+                // the timeout expired, refresh the status and check the
+                // `should_quit` variable below then loop again
+                report_status();
+            }
+            Err(e) => outln!("watch error: {e:?}"),
+        }
+        // Nothing changed as far as the watcher knows, so this isn't
+        // an attempt of the learner. The screen is left alone and the
+        // output only shown once the exercise passes, rather than printing
+        // the same failure over the prompt again and again.
+        if interval_due(interval, last_verified.get(), Instant::now()) {
+            match current_exercise.get() {
+                Some(current) => {
+                    let num_done = solved.borrow().len();
+                    let ((), output) =
+                        capture(|| verify_pending(pending_from(&current.path), num_done, false));
+                    if solved.borrow().contains(current.name.as_str()) {
+                        write_stdout(&output);
+                    }
+                    if solved.borrow().len() == exercises.len() {
+                        return Ok(WatchStatus::Finished);
+                    }
+                }
+                None => last_verified.set(Instant::now()),
             }
-            Err(e) => println!("watch error: {e:?}"),
         }
         // Check if we need to exit
         if should_quit.load(Ordering::SeqCst) {
@@ -583,9 +2880,152 @@ fn watch(exercises: &[Exercise], verbose: bool) -> notify::Result<WatchStatus> {
     }
 }
 
+// Whether the `--watch-interval-verify` interval passed since the last
+// verification. Any verification restarts it, so that the interval
+// doesn't verify again right after a file event did.
+fn interval_due(interval: Option<Duration>, last_verified: Instant, now: Instant) -> bool {
+    interval.is_some_and(|interval| now.saturating_duration_since(last_verified) >= interval)
+}
+
+// The content of the `watch --status-file`
+#[derive(Serialize)]
+struct StatusReport<'a> {
+    // The exercise watch mode is stuck on, if any
+    exercise: Option<&'a str>,
+    // The exercises that passed their last verification in watch mode
+    done: usize,
+    total: usize,
+    // Seconds since the Unix epoch
+    timestamp: u64,
+}
+
+// Replace the status file through a temporary file renamed over it,
+// so that probes never read a partially written one
+fn write_status_file(
+    path: &Path,
+    exercises: &[Exercise],
+    current: Option<&Exercise>,
+    done: usize,
+) -> io::Result<()> {
+    let status = StatusReport {
+        exercise: current.map(|exercise| exercise.name.as_str()),
+        done,
+        total: exercises.len(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+    };
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, serde_json::to_string(&status).unwrap())?;
+    fs::rename(&temp, path)
+}
+
+// The action terminating on Ctrl-C like the default one, registered while
+// no `InterruptFlag` is alive
+static INTERRUPT_DEFAULT: Mutex<Option<SigId>> = Mutex::new(None);
+
+// Sets its flag on Ctrl-C instead of terminating, until it is dropped.
+// signal-hook keeps its handler once the last action is unregistered, which
+// would ignore Ctrl-C from then on, so dropping the flag registers an action
+// terminating like the default one in its place.
+struct InterruptFlag {
+    id: SigId,
+}
+
+impl InterruptFlag {
+    fn register(flag: &Arc<AtomicBool>) -> io::Result<Self> {
+        let id = signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(flag))?;
+        if let Some(default) = INTERRUPT_DEFAULT.lock().unwrap().take() {
+            signal_hook::low_level::unregister(default);
+        }
+        Ok(InterruptFlag { id })
+    }
+}
+
+impl Drop for InterruptFlag {
+    fn drop(&mut self) {
+        signal_hook::low_level::unregister(self.id);
+        *INTERRUPT_DEFAULT.lock().unwrap() = signal_hook::flag::register_conditional_default(
+            signal_hook::consts::SIGINT,
+            Arc::new(AtomicBool::new(true)),
+        )
+        .ok();
+    }
+}
+
+// Run a single exercise again whenever its file is saved, until it passes
+fn run_watch(
+    exercise: &Exercise,
+    options: &RunOptions,
+    verbose: bool,
+) -> notify::Result<WatchStatus> {
+    let (tx, rx) = channel();
+    let should_quit = Arc::new(AtomicBool::new(false));
+    let _interrupt = InterruptFlag::register(&should_quit)?;
+    let dir = match exercise.path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let _watcher = ExercisesWatcher::new(dir, tx, None)?;
+
+    loop {
+        if run(exercise, options, verbose).is_ok() {
+            return Ok(WatchStatus::Finished);
+        }
+        outln!("Hint:");
+        print_hint(&FailedExercise::new(exercise).hint);
+        outln!("Waiting for {exercise} to be saved...");
+        loop {
+            if should_quit.load(Ordering::SeqCst) {
+                return Ok(WatchStatus::Unfinished);
+            }
+            match rx.recv_timeout(Duration::from_secs(1)) {
+                Ok(
+                    DebouncedEvent::Create(path)
+                    | DebouncedEvent::Write(path)
+                    | DebouncedEvent::Chmod(path),
+                ) if exercise.matches_path(&path) => break,
+                Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return Ok(WatchStatus::Unfinished),
+            }
+        }
+    }
+}
+
+// The space-separated compiler flags of an environment variable, which
+// rustc doesn't read by itself unlike Cargo
+fn env_flags(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+// Exit when rustup doesn't have the `--toolchain`, rather than failing
+// every exercise with its error
+fn check_toolchain(toolchain: &str) {
+    let output = Command::new("rustup")
+        .args(["run", toolchain, "rustc", "--version"])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => {
+            outln!("The toolchain {toolchain} isn't installed, install it with `rustup toolchain install {toolchain}`:");
+            outln!("{}", String::from_utf8_lossy(&output.stderr).trim_end());
+            std::process::exit(1);
+        }
+        Err(e) => {
+            outln!("Failed to run rustup, which --toolchain needs: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
@@ -621,37 +3061,499 @@ started, here's a couple of notes about how Rustlings operates:
 Got all that? Great! To get started, run `rustlings watch` in order to get the first
 exercise. Make sure to have your editor open!"#;
 
-const FENISH_LINE: &str = r#"+----------------------------------------------------+
-|          You made it to the Fe-nish line!          |
-+--------------------------  ------------------------+
-                          \\/
-     ▒▒          ▒▒▒▒▒▒▒▒      ▒▒▒▒▒▒▒▒          ▒▒
-   ▒▒▒▒  ▒▒    ▒▒        ▒▒  ▒▒        ▒▒    ▒▒  ▒▒▒▒
-   ▒▒▒▒  ▒▒  ▒▒            ▒▒            ▒▒  ▒▒  ▒▒▒▒
- ░░▒▒▒▒░░▒▒  ▒▒            ▒▒            ▒▒  ▒▒░░▒▒▒▒
-   ▓▓▓▓▓▓▓▓  ▓▓      ▓▓██  ▓▓  ▓▓██      ▓▓  ▓▓▓▓▓▓▓▓
-     ▒▒▒▒    ▒▒      ████  ▒▒  ████      ▒▒░░  ▒▒▒▒
-       ▒▒  ▒▒▒▒▒▒        ▒▒▒▒▒▒        ▒▒▒▒▒▒  ▒▒
-         ▒▒▒▒▒▒▒▒▒▒▓▓▓▓▓▓▒▒▒▒▒▒▒▒▓▓▒▒▓▓▒▒▒▒▒▒▒▒
-           ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒
-             ▒▒▒▒▒▒▒▒▒▒██▒▒▒▒▒▒██▒▒▒▒▒▒▒▒▒▒
-           ▒▒  ▒▒▒▒▒▒▒▒▒▒██████▒▒▒▒▒▒▒▒▒▒  ▒▒
-         ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒
-       ▒▒    ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒    ▒▒
-       ▒▒  ▒▒    ▒▒                  ▒▒    ▒▒  ▒▒
-           ▒▒  ▒▒                      ▒▒  ▒▒
-
-We hope you enjoyed learning about the various aspects of Rust!
-If you noticed any issues, please don't hesitate to report them to our repo.
-You can also contribute your own exercises to help the greater community!
-
-Before reporting an issue or contributing, please read our guidelines:
-https://github.com/rust-lang/rustlings/blob/main/CONTRIBUTING.md"#;
-
-const WELCOME: &str = r#"       welcome to...
-                 _   _ _
-  _ __ _   _ ___| |_| (_)_ __   __ _ ___
- | '__| | | / __| __| | | '_ \ / _` / __|
- | |  | |_| \__ \ |_| | | | | | (_| \__ \
- |_|   \__,_|___/\__|_|_|_| |_|\__, |___/
-                               |___/"#;
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_flaky_exercise_is_paused() {
+        let mut flaky = FlakyDetector::default();
+        let start = Instant::now();
+        let mut paused = vec![];
+        for i in 0..6 {
+            let now = start + Duration::from_secs(i);
+            paused.push(flaky.record("timer", 1, i % 2 == 0, now));
+        }
+        // The fourth flip happens on the fifth verification
+        assert_eq!(paused, [false, false, false, false, true, false]);
+        assert!(flaky.is_paused("timer", 1));
+        assert!(!flaky.is_paused("other", 1));
+
+        // Editing the exercise resumes verifying it
+        assert!(!flaky.is_paused("timer", 2));
+        assert!(!flaky.record("timer", 2, false, start + Duration::from_secs(7)));
+        assert!(!flaky.is_paused("timer", 2));
+    }
+
+    #[test]
+    fn test_slow_flips_are_not_flaky() {
+        let mut flaky = FlakyDetector::default();
+        let start = Instant::now();
+        for i in 0..10 {
+            let now = start + FLAKY_WINDOW * i;
+            assert!(!flaky.record("intro1", 1, i % 2 == 0, now));
+        }
+        assert!(!flaky.is_paused("intro1", 1));
+    }
+
+    #[test]
+    fn test_grading_profile() {
+        let args = MyVerifyArgs {
+            profile: Some(Profile::Grading),
+            jobs: None,
+            strict: false,
+            no_strict: false,
+            junit: None,
+            output: None,
+            format: ReportFormat::Plain,
+            fail_under: None,
+            shard: None,
+            report_template: None,
+            report_out: None,
+        };
+        let config = MyVerifyConfig::new(&args);
+        assert_eq!(config, MyVerifyConfig::grading());
+        assert!(config.strict);
+        assert_eq!(
+            config.junit,
+            Some(PathBuf::from(".github/result/junit.xml"))
+        );
+        assert_eq!(config.output, PathBuf::from(CHECK_RESULT_PATH));
+
+        let args = MyVerifyArgs {
+            jobs: Some(2),
+            output: Some(PathBuf::from("results.json")),
+            ..args
+        };
+        let config = MyVerifyConfig::new(&args);
+        assert_eq!(config.jobs, Some(2));
+        assert_eq!(config.output, PathBuf::from("results.json"));
+        assert!(config.strict);
+
+        let args = MyVerifyArgs {
+            profile: None,
+            ..args
+        };
+        let config = MyVerifyConfig::new(&args);
+        assert!(!config.strict);
+        assert_eq!(config.junit, None);
+
+        // Explicitly not strict, despite the profile
+        let config = MyVerifyConfig::new(&MyVerifyArgs {
+            profile: Some(Profile::Grading),
+            no_strict: true,
+            ..args
+        });
+        assert!(!config.strict);
+        assert_eq!(config.jobs, Some(2));
+    }
+
+    #[test]
+    fn test_topic_efforts() {
+        let exercise = |name: &str, topic: Option<&str>| Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("exercises/{name}.rs")),
+            topic: topic.map(String::from),
+            ..Default::default()
+        };
+        let exercises = [
+            exercise("structs1", Some("structs")),
+            exercise("enums1", Some("enums")),
+            exercise("structs2", Some("structs")),
+            exercise("quiz1", None),
+            exercise("enums2", Some("enums")),
+        ];
+        let effort = |attempts, seconds| Effort { attempts, seconds };
+        let efforts = HashMap::from([
+            ("exercises/structs1.rs".to_string(), effort(2, 30)),
+            ("exercises/structs2.rs".to_string(), effort(6, 90)),
+            ("exercises/enums1.rs".to_string(), effort(1, 10)),
+            ("exercises/quiz1.rs".to_string(), effort(3, 0)),
+        ]);
+
+        let topics = topic_efforts(&exercises, &efforts);
+        assert_eq!(
+            topics,
+            [
+                TopicEffort {
+                    topic: "structs".into(),
+                    exercises: 2,
+                    effort: effort(8, 120),
+                },
+                TopicEffort {
+                    topic: "uncategorized".into(),
+                    exercises: 1,
+                    effort: effort(3, 0),
+                },
+                TopicEffort {
+                    topic: "enums".into(),
+                    exercises: 1,
+                    effort: effort(1, 10),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_certificate() {
+        let exercise = |name: &str, topic: &str| Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("exercises/{name}.rs")),
+            topic: Some(topic.into()),
+            ..Default::default()
+        };
+        let exercises = [
+            exercise("intro1", "intro"),
+            exercise("structs1", "structs"),
+            exercise("structs2", "structs"),
+        ];
+        let data_gather = DataGather::new(PathBuf::from("tests/fixture/certificate/data.jsonl"));
+        let certificate = Certificate::new(&exercises, &data_gather.efforts().unwrap());
+
+        assert_eq!(certificate.exercises_solved, 3);
+        assert_eq!(certificate.attempts, 6);
+        assert_eq!(certificate.seconds, 400);
+        let markdown = certificate.to_markdown();
+        assert!(markdown.contains("Solved all 3 exercises in 6m 40s, with 6 failed attempts"));
+        assert!(markdown.contains("| structs | 2 | 5 | 5m 20s |\n| intro | 1 | 1 | 1m 20s |\n"));
+    }
+
+    #[test]
+    fn test_results_in_exercise_order() {
+        let order: Vec<String> = ["intro1", "intro2", "variables1", "variables2"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let result = |name: &str, result| ExerciseResult {
+            name: name.into(),
+            result,
+        };
+        let mut results = vec![
+            result("variables1", true),
+            result("intro2", false),
+            result("variables2", true),
+            result("intro1", true),
+        ];
+        sort_results(&mut results, &order);
+
+        let serialized = serde_json::to_value(&results).unwrap();
+        let names: Vec<_> = serialized
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, order);
+        assert_eq!(serialized[1]["result"], false);
+    }
+
+    #[test]
+    fn test_no_clear_keeps_output() {
+        assert_eq!(screen_break(false, true), CLEAR_SCREEN);
+        assert_eq!(screen_break(false, false), "");
+        for is_term in [true, false] {
+            let screen_break = screen_break(true, is_term);
+            assert!(!screen_break.contains('\x1b'));
+            assert!(screen_break.contains("===="));
+        }
+    }
+
+    #[test]
+    fn test_interval_verify() {
+        let interval = Some(Duration::from_secs(10));
+        let verified = Instant::now();
+        assert!(!interval_due(
+            None,
+            verified,
+            verified + Duration::from_secs(60)
+        ));
+        assert!(!interval_due(
+            interval,
+            verified,
+            verified + Duration::from_secs(9)
+        ));
+        assert!(interval_due(
+            interval,
+            verified,
+            verified + Duration::from_secs(10)
+        ));
+
+        // A verification after a file event restarts the interval
+        let after_event = verified + Duration::from_secs(8);
+        assert!(!interval_due(
+            interval,
+            after_event,
+            verified + Duration::from_secs(12)
+        ));
+    }
+
+    #[test]
+    fn test_closest_name() {
+        let exercises: Vec<Exercise> = ["variables1", "variables2", "functions1", "if1"]
+            .iter()
+            .map(|name| Exercise {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(closest_name("varibles1", &exercises), Some("variables1"));
+        assert_eq!(closest_name("functons1", &exercises), Some("functions1"));
+        assert_eq!(closest_name("if2", &exercises), Some("if1"));
+        assert_eq!(closest_name("threads3", &exercises), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_inotify_warning() {
+        assert_eq!(inotify_warning(40, 8192), None);
+        let warning = inotify_warning(30, 32).unwrap();
+        assert!(warning.starts_with("Watching the exercises takes 30 of the 32 inotify watches"));
+        assert!(warning.contains("sysctl fs.inotify.max_user_watches="));
+
+        let dir = std::env::temp_dir().join(format!("rustlings_inotify_{}", std::process::id()));
+        fs::create_dir_all(dir.join("intro")).unwrap();
+        fs::create_dir_all(dir.join("structs/nested")).unwrap();
+        fs::write(dir.join("intro/intro1.rs"), "").unwrap();
+        let directories = count_directories(&dir);
+        let _ignored = fs::remove_dir_all(&dir);
+        assert_eq!(directories, 4);
+    }
+
+    #[test]
+    fn test_command_completion() {
+        assert_eq!(command_candidates("h"), ["hint", "help"]);
+        assert_eq!(command_candidates(" cl"), ["clear"]);
+        assert_eq!(command_candidates("quit"), ["quit"]);
+        assert_eq!(command_candidates("").len(), WATCH_COMMANDS.len());
+        assert!(command_candidates("x").is_empty());
+
+        let history = DefaultHistory::new();
+        let context = Context::new(&history);
+        let (start, candidates) = WatchHelper.complete("  re", 4, &context).unwrap();
+        assert_eq!(start, 2);
+        assert_eq!(candidates, ["retry", "report"]);
+    }
+
+    #[test]
+    fn test_shards_cover_exercises() {
+        let exercises: Vec<usize> = (0..10).collect();
+        let mut covered: Vec<usize> = (1..=3)
+            .flat_map(|index| Shard { index, count: 3 }.select(exercises.clone()))
+            .collect();
+        assert_eq!(covered.len(), exercises.len());
+        covered.sort();
+        assert_eq!(covered, exercises);
+        assert_eq!(Shard::from_str("2/3").unwrap().select(exercises), [1, 4, 7]);
+
+        assert_eq!("1/1".parse(), Ok(Shard { index: 1, count: 1 }));
+        assert!("0/3".parse::<Shard>().is_err());
+        assert!("4/3".parse::<Shard>().is_err());
+        assert!("2".parse::<Shard>().is_err());
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let results = [
+            ExerciseResult {
+                name: "intro1".into(),
+                result: true,
+            },
+            ExerciseResult {
+                name: "a<b".into(),
+                result: false,
+            },
+        ];
+        let report = junit_report(&results);
+        assert!(report.contains(r#"tests="2" failures="1""#));
+        assert!(report.contains(r#"<testcase name="intro1"/>"#));
+        assert!(report.contains(r#"<failure message="a&lt;b failed"/>"#));
+    }
+
+    #[test]
+    fn test_report_template() {
+        let check_list = ExerciseCheckList {
+            exercises: vec![
+                ExerciseResult {
+                    name: "intro1".into(),
+                    result: true,
+                },
+                ExerciseResult {
+                    name: "intro2".into(),
+                    result: false,
+                },
+            ],
+            user_name: Some("ferris".into()),
+            statistics: ExerciseStatistics {
+                total_exercations: 2,
+                total_succeeds: 1,
+                total_failures: 1,
+                score: 1,
+                max_score: 2,
+            },
+        };
+        let template = "# {{user_name}}: {{statistics.score}}/{{statistics.max_score}}\n\
+                        {{#each exercises}}- {{name}} {{#if result}}passed{{else}}failed{{/if}}\n{{/each}}";
+        let report = report_renderer(template)
+            .unwrap()
+            .render(REPORT_TEMPLATE, &check_list)
+            .unwrap();
+        assert_eq!(report, "# ferris: 1/2\n- intro1 passed\n- intro2 failed\n");
+
+        assert!(report_renderer("{{#each exercises}}").is_err());
+        let misspelled = report_renderer("{{statistics.scor}}").unwrap();
+        assert!(misspelled.render(REPORT_TEMPLATE, &check_list).is_err());
+    }
+
+    #[test]
+    fn test_weighted_score() {
+        let exercise = |name: &str, points| Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("exercises/{name}.rs")),
+            mode: exercise::Mode::Compile,
+            points,
+            ..Default::default()
+        };
+        let exercises = [
+            exercise("intro1", None),
+            exercise("intro2", Some(3)),
+            exercise("intro3", Some(5)),
+        ];
+        let mut statistics = ExerciseStatistics::new(&exercises);
+        assert_eq!(statistics.max_score, 9);
+
+        for (exercise, passed) in exercises.iter().zip([true, true, false]) {
+            statistics.record(exercise.points(), passed);
+        }
+        assert_eq!(statistics.score, 4);
+        assert_eq!(statistics.total_succeeds, 2);
+        assert_eq!(statistics.total_failures, 1);
+    }
+
+    #[test]
+    fn test_removed_exercise() {
+        let exercises = [Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/intro/intro1.rs"),
+            mode: exercise::Mode::Compile,
+            ..Default::default()
+        }];
+        let removed = PathBuf::from("/home/learner/rustlings/exercises/intro/intro1.rs");
+        let found = removed_exercise(&exercises, &DebouncedEvent::Remove(removed.clone()));
+        assert_eq!(found.map(|e| e.name.as_str()), Some("intro1"));
+
+        let other = PathBuf::from("/home/learner/rustlings/exercises/intro/scratch.rs");
+        assert!(removed_exercise(&exercises, &DebouncedEvent::Remove(other)).is_none());
+        assert!(removed_exercise(&exercises, &DebouncedEvent::Write(removed)).is_none());
+    }
+
+    #[test]
+    fn test_open_failed_exercise() {
+        let exercise = Exercise {
+            name: "intro1".into(),
+            path: PathBuf::from("exercises/intro/intro1.rs"),
+            mode: exercise::Mode::Compile,
+            hint: Some("Remove the marker".into()),
+            ..Default::default()
+        };
+        let failed_exercise = Mutex::new(Some(FailedExercise::new(&exercise)));
+        let failed = failed_exercise.lock().unwrap();
+        let failed = failed.as_ref().unwrap();
+        assert_eq!(failed.hint, "Remove the marker");
+
+        let command = open_command(Some("code --wait"), &failed.path).unwrap();
+        assert_eq!(command.get_program(), "code");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["--wait", "exercises/intro/intro1.rs"]);
+        assert!(open_command(None, &failed.path).is_none());
+        assert!(open_command(Some(" "), &failed.path).is_none());
+    }
+
+    #[test]
+    fn test_split_option_values() {
+        let args: Vec<String> = ["verify", "--fail-fast=false", "-v", "--", "--name=value"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            split_option_values(&args),
+            ["verify", "--fail-fast", "false", "-v", "--", "--name=value"]
+        );
+    }
+
+    #[test]
+    fn test_split_option_values_keeps_values_whole() {
+        let args: Vec<String> = ["watch", "--on-pass=echo a=b", "-n=3", "a=b"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            split_option_values(&args),
+            ["watch", "--on-pass", "echo a=b", "-n=3", "a=b"]
+        );
+    }
+
+    #[test]
+    fn test_without_bundle() {
+        let args: Vec<String> = ["--bundle", "set.zip", "list", "--", "--bundle"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(without_bundle(&args), ["list", "--", "--bundle"]);
+    }
+
+    #[test]
+    fn test_poll_watcher_reports_changes() {
+        let dir = env::temp_dir().join(format!("rustlings_poll_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (tx, rx) = channel();
+        let watcher = ExercisesWatcher::new(&dir, tx, Some(50)).unwrap();
+        assert!(matches!(watcher, ExercisesWatcher::Poll { .. }));
+
+        fs::write(dir.join("intro1.rs"), "fn main() {}").unwrap();
+        let event = rx.recv_timeout(Duration::from_secs(5));
+        drop(watcher);
+        let _ignored = fs::remove_dir_all(&dir);
+        assert!(matches!(event, Ok(DebouncedEvent::Create(path)) if path.ends_with("intro1.rs")));
+    }
+
+    #[test]
+    fn test_extra_path_changes_are_reported() {
+        let dir = env::temp_dir().join(format!("rustlings_extra_{}", std::process::id()));
+        let extra = dir.join("helpers");
+        fs::create_dir_all(dir.join("exercises")).unwrap();
+        fs::create_dir_all(&extra).unwrap();
+        let (tx, rx) = channel();
+        let mut watcher = ExercisesWatcher::new(&dir.join("exercises"), tx, Some(50)).unwrap();
+        watcher.watch(&extra).unwrap();
+
+        fs::write(extra.join("shared.rs"), "pub fn helper() {}").unwrap();
+        let event = rx.recv_timeout(Duration::from_secs(5));
+        drop(watcher);
+        let changed = match event {
+            Ok(DebouncedEvent::Create(path)) => is_extra_path(std::slice::from_ref(&extra), &path),
+            _ => false,
+        };
+        let exercise = dir.join("exercises/intro1.rs");
+        fs::write(&exercise, "fn main() {}").unwrap();
+        let not_extra = is_extra_path(&[extra], &exercise);
+        let _ignored = fs::remove_dir_all(&dir);
+        assert!(changed);
+        assert!(!not_extra);
+    }
+
+    #[test]
+    fn test_watch_retry_requests_rescan() {
+        let (tx, rx) = channel();
+        let hint = Mutex::new(None);
+        let should_quit = AtomicBool::new(false);
+        let data_gather = DataGather::new(env::temp_dir().join("rustlings_retry_data.jsonl"));
+
+        watch_command("retry", &hint, &should_quit, &tx, &data_gather);
+        assert_eq!(rx.try_recv().unwrap(), DebouncedEvent::Rescan);
+        watch_command("hint", &hint, &should_quit, &tx, &data_gather);
+        assert!(rx.try_recv().is_err());
+        assert!(!should_quit.load(Ordering::SeqCst));
+    }
+}
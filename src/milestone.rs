@@ -0,0 +1,218 @@
+use crate::exercise::Exercise;
+use crate::verify::chapter_of;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// The numbers of solved exercises celebrated unless the manifest sets its own
+pub const DEFAULT_COUNTS: [usize; 3] = [10, 25, 50];
+
+// The milestones of the learner's progress celebrated in watch mode.
+// The reached ones are kept in a file so each is only celebrated once.
+pub struct Milestones {
+    path: PathBuf,
+    // Numbers of solved exercises worth celebrating
+    counts: Vec<usize>,
+    // Keys of the milestones already reached, like "solved-10"
+    reached: BTreeSet<String>,
+    // Whether the next check only records the milestones reached before
+    // they were tracked, without celebrating them
+    quiet: bool,
+}
+
+impl Milestones {
+    // Load the milestones reached so far. Without a file yet, those reached
+    // before milestones were tracked are recorded by the first check
+    // without celebrating them.
+    pub fn load(path: PathBuf, counts: Vec<usize>) -> io::Result<Self> {
+        let mut milestones = Milestones {
+            path,
+            counts,
+            reached: BTreeSet::new(),
+            quiet: false,
+        };
+        match fs::read_to_string(&milestones.path) {
+            Ok(content) => {
+                milestones.reached = serde_json::from_str(&content)?;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => milestones.quiet = true,
+            Err(e) => return Err(e),
+        }
+        Ok(milestones)
+    }
+
+    // The messages of the milestones reached since the last check, with the
+    // exercises `solved` according to their verification
+    pub fn check(
+        &mut self,
+        exercises: &[Exercise],
+        solved: impl Fn(&Exercise) -> bool,
+    ) -> io::Result<Vec<String>> {
+        let progress: Vec<_> = exercises.iter().map(|e| (e, solved(e))).collect();
+        let messages = self.record(&progress)?;
+        if self.quiet {
+            self.quiet = false;
+            return Ok(Vec::new());
+        }
+        Ok(messages)
+    }
+
+    // Record the milestones reached with the given exercises and whether
+    // each is done, returning the messages of the new ones
+    fn record(&mut self, progress: &[(&Exercise, bool)]) -> io::Result<Vec<String>> {
+        let new: Vec<(String, String)> = reached(progress, &self.counts)
+            .into_iter()
+            .filter(|(key, _)| !self.reached.contains(key))
+            .collect();
+        if new.is_empty() && self.path.exists() {
+            return Ok(Vec::new());
+        }
+        for (key, _) in &new {
+            self.reached.insert(key.clone());
+        }
+        fs::write(&self.path, serde_json::to_string(&self.reached)?)?;
+        Ok(new.into_iter().map(|(_, message)| message).collect())
+    }
+}
+
+// The keys and messages of every milestone reached
+fn reached(progress: &[(&Exercise, bool)], counts: &[usize]) -> Vec<(String, String)> {
+    let solved = progress.iter().filter(|(_, done)| *done).count();
+    let mut milestones = Vec::new();
+    if solved >= 1 {
+        milestones.push((
+            String::from("first"),
+            String::from("You solved your first exercise, welcome aboard!"),
+        ));
+    }
+    for &count in counts.iter().filter(|&&count| solved >= count) {
+        milestones.push((
+            format!("solved-{count}"),
+            format!("That's {count} exercises solved, keep it up!"),
+        ));
+    }
+    let mut chapters: Vec<String> = Vec::new();
+    for (exercise, _) in progress {
+        let chapter = chapter_of(exercise);
+        if !chapters.contains(&chapter) {
+            chapters.push(chapter);
+        }
+    }
+    for chapter in chapters {
+        let finished = progress
+            .iter()
+            .filter(|(exercise, _)| chapter_of(exercise) == chapter)
+            .all(|(_, done)| *done);
+        if finished {
+            milestones.push((
+                format!("chapter-{chapter}"),
+                format!("You finished the {chapter} chapter!"),
+            ));
+        }
+    }
+    if !progress.is_empty() && solved == progress.len() {
+        milestones.push((
+            String::from("all"),
+            format!("You solved all {solved} exercises!"),
+        ));
+    }
+    milestones
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exercise(name: &str, chapter: &str) -> Exercise {
+        Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("exercises/{chapter}/{name}.rs")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_milestone_fires_once() {
+        let path =
+            std::env::temp_dir().join(format!("rustlings_milestones_{}", std::process::id()));
+        let _ignored = fs::remove_file(&path);
+        let exercises = [
+            exercise("intro1", "intro"),
+            exercise("structs1", "structs"),
+            exercise("structs2", "structs"),
+        ];
+        let mut milestones = Milestones {
+            path: path.clone(),
+            counts: vec![2],
+            reached: BTreeSet::new(),
+            quiet: false,
+        };
+
+        let progress = |done: usize| -> Vec<(&Exercise, bool)> {
+            exercises
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (e, i < done))
+                .collect()
+        };
+        assert_eq!(
+            milestones.record(&progress(0)).unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            milestones.record(&progress(1)).unwrap(),
+            [
+                "You solved your first exercise, welcome aboard!",
+                "You finished the intro chapter!"
+            ]
+        );
+        assert_eq!(
+            milestones.record(&progress(2)).unwrap(),
+            ["That's 2 exercises solved, keep it up!"]
+        );
+        assert_eq!(
+            milestones.record(&progress(2)).unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            milestones.record(&progress(3)).unwrap(),
+            [
+                "You finished the structs chapter!",
+                "You solved all 3 exercises!"
+            ]
+        );
+
+        // Reached milestones are remembered across sessions
+        let mut reloaded = Milestones::load(path.clone(), vec![2]).unwrap();
+        let messages = reloaded.check(&exercises, |_| true).unwrap();
+        let _ignored = fs::remove_file(&path);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_milestones_reached_before_tracking_are_not_celebrated() {
+        let path = std::env::temp_dir().join(format!(
+            "rustlings_milestones_untracked_{}",
+            std::process::id()
+        ));
+        let _ignored = fs::remove_file(&path);
+        let exercises = [exercise("intro1", "intro"), exercise("structs1", "structs")];
+
+        let mut milestones = Milestones::load(path.clone(), vec![2]).unwrap();
+        let first = milestones
+            .check(&exercises, |e| e.name == "intro1")
+            .unwrap();
+        let second = milestones.check(&exercises, |_| true).unwrap();
+        let _ignored = fs::remove_file(&path);
+        assert!(first.is_empty());
+        assert_eq!(
+            second,
+            [
+                "That's 2 exercises solved, keep it up!",
+                "You finished the structs chapter!",
+                "You solved all 2 exercises!"
+            ]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::exercise::Exercise;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// What importing a progress archive did
+#[derive(Default, Debug)]
+pub struct Imported {
+    // The exercises whose solution was restored
+    pub restored: Vec<String>,
+    // The exercises solved differently here, kept as they are
+    pub conflicts: Vec<String>,
+    // Files of the archive that aren't exercises of this checkout
+    pub unknown: Vec<PathBuf>,
+}
+
+// Write the solved exercises into a .tar.gz archive, under their paths
+// relative to the rustlings directory. Returns how many were written.
+pub fn export(exercises: &[Exercise], archive: &Path) -> Result<usize, Box<dyn Error>> {
+    let file = File::create(archive)
+        .map_err(|e| format!("Failed to create {}: {e}", archive.display()))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut exported = 0;
+    for exercise in exercises.iter().filter(|e| e.looks_done()) {
+        builder.append_path_with_name(&exercise.path, &exercise.path)?;
+        exported += 1;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(exported)
+}
+
+// Restore the solutions of an archive written by `export`. Exercises
+// already solved here are only overwritten with `force`, and files that
+// aren't exercises of this checkout are never written.
+pub fn import(
+    exercises: &[Exercise],
+    archive: &Path,
+    force: bool,
+) -> Result<Imported, Box<dyn Error>> {
+    let file =
+        File::open(archive).map_err(|e| format!("Failed to open {}: {e}", archive.display()))?;
+    let mut imported = Imported::default();
+    for entry in tar::Archive::new(GzDecoder::new(file)).entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Some(exercise) = exercises.iter().find(|e| e.path == path) else {
+            imported.unknown.push(path);
+            continue;
+        };
+        let mut solution = String::new();
+        entry.read_to_string(&mut solution)?;
+        let current = fs::read_to_string(&exercise.path).ok();
+        if current.as_deref() == Some(solution.as_str()) {
+            continue;
+        }
+        if current.is_some() && exercise.looks_done() && !force {
+            imported.conflicts.push(exercise.name.clone());
+            continue;
+        }
+        if let Some(dir) = exercise.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&exercise.path, solution)?;
+        imported.restored.push(exercise.name.clone());
+    }
+    Ok(imported)
+}
@@ -1,8 +1,20 @@
 use glob::glob;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
+/// Where `lsp` remembers the detected sysroot between runs
+pub const SYSROOT_CACHE_PATH: &str = "./.rustlings-sysroot.json";
+
+/// The sysroot detected for a toolchain, reused while the toolchain stays the same
+#[derive(Serialize, Deserialize)]
+struct SysrootCache {
+    rustc_version: String,
+    sysroot_src: String,
+}
+
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
 #[derive(Serialize, Deserialize)]
@@ -62,6 +74,39 @@ impl RustAnalyzerProject {
         Ok(())
     }
 
+    /// Reuse the sysroot cached at `cache` unless `refresh` is set, the
+    /// toolchain changed or the cached path is gone, in which case it is
+    /// determined again and cached. Returns whether it was determined.
+    pub fn get_sysroot_src_cached(
+        &mut self,
+        cache: &Path,
+        refresh: bool,
+    ) -> Result<bool, Box<dyn Error>> {
+        let version = Command::new("rustc").arg("--version").output()?.stdout;
+        let rustc_version = String::from_utf8_lossy(&version).trim().to_string();
+        if !refresh {
+            let cached = fs::read_to_string(cache)
+                .ok()
+                .and_then(|cached| serde_json::from_str::<SysrootCache>(&cached).ok());
+            if let Some(cached) = cached {
+                if cached.rustc_version == rustc_version && Path::new(&cached.sysroot_src).exists()
+                {
+                    outln!("Using the cached toolchain: {}\n", cached.sysroot_src);
+                    self.sysroot_src = cached.sysroot_src;
+                    return Ok(false);
+                }
+            }
+        }
+
+        self.get_sysroot_src()?;
+        let cached = SysrootCache {
+            rustc_version,
+            sysroot_src: self.sysroot_src.clone(),
+        };
+        fs::write(cache, serde_json::to_vec(&cached)?)?;
+        Ok(true)
+    }
+
     /// Use `rustc` to determine the default toolchain
     pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
         let toolchain = Command::new("rustc")
@@ -75,9 +120,9 @@ impl RustAnalyzerProject {
 
         let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
 
-        println!("Determined toolchain: {}\n", &toolchain);
+        outln!("Determined toolchain: {}\n", &toolchain);
 
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
+        self.sysroot_src = (std::path::Path::new(toolchain)
             .join("lib")
             .join("rustlib")
             .join("src")
@@ -88,3 +133,40 @@ impl RustAnalyzerProject {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write_cache(cache: &Path, sysroot_src: &str) {
+        let version = Command::new("rustc").arg("--version").output().unwrap();
+        let cached = SysrootCache {
+            rustc_version: String::from_utf8_lossy(&version.stdout).trim().to_string(),
+            sysroot_src: sysroot_src.to_string(),
+        };
+        fs::write(cache, serde_json::to_vec(&cached).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_sysroot_cache() {
+        let cache = std::env::temp_dir().join(format!("rustlings_sysroot_{}", std::process::id()));
+        let cached_src = std::env::temp_dir().to_string_lossy().to_string();
+        let mut project = RustAnalyzerProject::new();
+
+        write_cache(&cache, &cached_src);
+        let detected = project.get_sysroot_src_cached(&cache, false).unwrap();
+        let sysroot_src = project.sysroot_src.clone();
+        let refreshed = project.get_sysroot_src_cached(&cache, true).unwrap();
+
+        // A cached path that doesn't exist anymore is determined again
+        write_cache(&cache, "/nonexistent/rustlib");
+        let stale = project.get_sysroot_src_cached(&cache, false).unwrap();
+        let _ignored = fs::remove_file(&cache);
+
+        assert!(!detected);
+        assert_eq!(sysroot_src, cached_src);
+        assert!(refreshed);
+        assert!(stale);
+        assert_ne!(project.sysroot_src, "/nonexistent/rustlib");
+    }
+}
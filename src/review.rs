@@ -0,0 +1,38 @@
+use crate::data_gather::timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+// A problem with an exercise noticed while solving it, reported from
+// watch mode so it can be fixed later
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReviewEntry {
+    // Name of the reported exercise
+    pub exercise: String,
+    // What needs a review, in the reporter's words
+    pub message: String,
+    // Seconds since the Unix epoch at which it was reported
+    pub timestamp: u64,
+}
+
+impl ReviewEntry {
+    pub fn now(exercise: &str, message: &str) -> Self {
+        ReviewEntry {
+            exercise: exercise.to_string(),
+            message: message.to_string(),
+            timestamp: timestamp(),
+        }
+    }
+}
+
+// Append an entry to the review log, one JSON object per line
+pub fn append(path: &Path, entry: &ReviewEntry) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())
+}
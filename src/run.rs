@@ -1,22 +1,170 @@
-use std::process::Command;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{self, Command};
+use std::thread::{self, JoinHandle};
 
-use crate::exercise::{Exercise, Mode};
-use crate::verify::{test, FailedType, VerifyFailed};
-use indicatif::ProgressBar;
+use crate::data_gather::fnv_hash;
+use crate::exercise::{Compiler, Exercise, Mode, RunOptions};
+use crate::ui::spinner;
+use crate::verify::{
+    check_output, run_check_command, run_under_miri, test, FailedType, VerifyFailed,
+};
+use serde::{Deserialize, Deserializer, Serialize};
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), VerifyFailed> {
+pub fn run(exercise: &Exercise, options: &RunOptions, verbose: bool) -> Result<(), VerifyFailed> {
+    if exercise.runs_under_miri(options) {
+        run_under_miri(exercise, options)?;
+        success!("Successfully ran {} under Miri", exercise);
+        return Ok(());
+    }
+    if exercise.check_command.is_some() {
+        run_check_command(exercise, options)?;
+        success!("Successfully checked {}", exercise);
+        return Ok(());
+    }
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
+        Mode::Test if options.no_run => compile_tests(exercise, options)?,
+        Mode::Test => test(exercise, options, verbose)?,
+        Mode::Compile => compile_and_run(exercise, options)?,
+        Mode::Clippy => compile_and_run(exercise, options)?,
+        Mode::Output => compile_and_run(exercise, options)?,
     }
     Ok(())
 }
 
+// Print the `rustc --explain` text for the first error code
+// of a failed compilation, if the compiler reported one.
+pub fn explain(failed: &VerifyFailed) {
+    if failed.kind != FailedType::Compilation {
+        return;
+    }
+    match failed.error_codes.first() {
+        Some(code) => match explain_error_code(code) {
+            Some(explanation) => {
+                outln!("Explanation of {code}:\n");
+                outln!("{explanation}");
+            }
+            None => warn!("Could not fetch the explanation of {}", code),
+        },
+        None => outln!("The compiler output doesn't contain an error code to explain."),
+    }
+}
+
+// Fetch the explanation of an error code from `rustc --explain`
+fn explain_error_code(code: &str) -> Option<String> {
+    let output = Command::new("rustc")
+        .args(["--explain", code])
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+// A compiler message as reported by `rustc --error-format=json`,
+// keeping the parts editors need to place it
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Diagnostic {
+    pub level: String,
+    pub message: String,
+    // The error code like "E0308", if there is one
+    #[serde(default, deserialize_with = "error_code")]
+    pub code: Option<String>,
+    pub spans: Vec<DiagnosticSpan>,
+    // Notes and help, which carry the suggestions
+    #[serde(default)]
+    pub children: Vec<Diagnostic>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    // The replacement text of a suggestion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_replacement: Option<String>,
+}
+
+// rustc reports the code as an object with its explanation
+fn error_code<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct Code {
+        code: String,
+    }
+    Ok(Option::<Code>::deserialize(deserializer)?.map(|code| code.code))
+}
+
+// The diagnostics of the JSON compiler output, one object per line.
+// Summaries like "aborting due to previous error" point at no code and are left out.
+pub fn parse_diagnostics(output: &str) -> Vec<Diagnostic> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+        .filter(|diagnostic| !diagnostic.spans.is_empty())
+        .collect()
+}
+
+// Compile the exercise again to collect its structured diagnostics
+pub fn diagnostics(exercise: &Exercise, options: &RunOptions) -> io::Result<Vec<Diagnostic>> {
+    Ok(parse_diagnostics(&exercise.check_json(options)?))
+}
+
+// Compile a trivial program so the compiler and the standard library are
+// loaded from disk before the first exercise is compiled. A marker file in
+// the temporary directory, named after the `rustc -vV` of the compiler,
+// remembers that its toolchain was warmed up, in which case nothing is
+// compiled and false is returned.
+pub fn warmup(compiler: &Compiler) -> io::Result<bool> {
+    warmup_in(compiler, &env::temp_dir())
+}
+
+fn warmup_in(compiler: &Compiler, temp_dir: &Path) -> io::Result<bool> {
+    let version = fnv_hash(compiler.version()?.as_bytes());
+    let marker = temp_dir.join(format!("rustlings_warmup_{version:016x}"));
+    if marker.exists() {
+        return Ok(false);
+    }
+
+    let progress_bar = spinner("Warming up the compiler...".to_string());
+
+    // Named after the process, so that concurrent warm-ups don't clash
+    let source = temp_dir.join(format!("rustlings_warmup_{}.rs", process::id()));
+    let binary = temp_dir.join(format!("rustlings_warmup_{}", process::id()));
+    fs::write(
+        &source,
+        "fn main() {\n    println!(\"Hello, warm world!\");\n}\n",
+    )?;
+    let output = warmup_command(compiler, &source, &binary).output();
+    let _ignored = fs::remove_file(&source);
+    let _ignored = fs::remove_file(&binary);
+    progress_bar.finish_and_clear();
+
+    if !output?.status.success() {
+        return Err(io::Error::other("the warm-up program failed to compile"));
+    }
+    fs::write(marker, "")?;
+    Ok(true)
+}
+
+fn warmup_command(compiler: &Compiler, source: &Path, binary: &Path) -> Command {
+    let mut command = compiler.command();
+    command.arg(source).arg("-o").arg(binary);
+    command
+}
+
 // Resets the exercise by stashing the changes.
 pub fn reset(exercise: &Exercise) -> Result<(), ()> {
     let command = Command::new("git")
@@ -30,15 +178,155 @@ pub fn reset(exercise: &Exercise) -> Result<(), ()> {
     }
 }
 
+// Run an exercise in output mode and write what it prints to its golden
+// file, which verifying it compares with from then on
+pub fn bless(exercise: &Exercise, options: &RunOptions) -> Result<(), ()> {
+    if !matches!(exercise.mode, Mode::Output) {
+        warn!(
+            "Only exercises in output mode can be blessed, {} isn't one",
+            exercise
+        );
+        return Err(());
+    }
+    if exercise.expected_output.is_some() {
+        warn!(
+            "{} has an inline expected_output, remove it to compare with a golden file instead",
+            exercise
+        );
+        return Err(());
+    }
+    let compilation = exercise.compile(options).map_err(|output| {
+        warn!(
+            "Compilation of {} failed!, Compiler error message:\n",
+            exercise
+        );
+        outln!("{}", output.stderr);
+    })?;
+    let output = compilation.run().map_err(|output| {
+        warn!("{} failed, its output wasn't blessed:", exercise);
+        outln!("{}", output.stderr);
+    })?;
+    let golden = exercise.golden_path();
+    fs::write(&golden, &output.stdout).map_err(|e| {
+        warn!("Failed to write the golden file: {}", e);
+    })?;
+    success!("Blessed the output of {}", exercise);
+    outln!("Wrote {}", golden.display());
+    Ok(())
+}
+
+// Replace an exercise with its reference solution, to prepare answer keys.
+// The changes to the exercise are stashed first like `reset` does, unless
+// `force` allows overwriting an exercise git doesn't track.
+pub fn reset_to_solution(exercise: &Exercise, force: bool) -> Result<(), ()> {
+    let solution = exercise.solution_path();
+    if !solution.exists() {
+        warn!("{} has no solution to reset it to", exercise);
+        outln!("Solutions are looked up at {}", solution.display());
+        return Err(());
+    }
+    // Without changes git succeeds without stashing anything
+    let before = stash_top();
+    let tracked = Command::new("git")
+        .args(["stash", "push", "--quiet", "--"])
+        .arg(&exercise.path)
+        .status()
+        .is_ok_and(|status| status.success());
+    let stashed = tracked && stash_top() != before;
+    if !tracked && !force {
+        warn!(
+            "Failed to stash the changes of {}, it wasn't overwritten",
+            exercise
+        );
+        outln!("Run it again with --force to overwrite it without keeping them.");
+        return Err(());
+    }
+    if let Err(e) = fs::copy(&solution, &exercise.path) {
+        warn!("Failed to copy the solution: {}", e);
+        return Err(());
+    }
+    warn!(
+        "{} now contains the reference solution, don't hand it out before it's solved!",
+        exercise
+    );
+    if stashed {
+        outln!(
+            "Your changes were stashed, run `git checkout -- {0} && git stash pop` to restore them.",
+            exercise.path.display()
+        );
+    } else {
+        outln!(
+            "Run `rustlings reset {}` to restore the exercise.",
+            exercise.name
+        );
+    }
+    Ok(())
+}
+
+// The commit of the latest git stash entry, if there is one
+fn stash_top() -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--quiet", "--verify", "refs/stash"])
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+// Runs the user's `--on-pass` shell command for an exercise that turned to
+// passing. The command runs in the background so it doesn't block the watch
+// loop, and a non-zero exit is only reported as a warning.
+pub fn on_pass(command: &str, exercise: &Exercise) -> JoinHandle<()> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let child = Command::new(shell)
+        .args([flag, command])
+        .env("RUSTLINGS_EXERCISE", &exercise.name)
+        .env("RUSTLINGS_EXERCISE_PATH", &exercise.path)
+        .spawn();
+
+    thread::spawn(move || match child.and_then(|mut child| child.wait()) {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("The on-pass command exited with {}", status),
+        Err(e) => warn!("Failed to run the on-pass command: {}", e),
+    })
+}
+
+// Compile the test harness of an exercise without running its tests
+fn compile_tests(exercise: &Exercise, options: &RunOptions) -> Result<(), VerifyFailed> {
+    let progress_bar = spinner(format!("Compiling the tests of {exercise}..."));
+    let result = exercise.compile(options);
+    progress_bar.finish_and_clear();
+
+    match result {
+        Ok(_compilation) => {
+            success!("Successfully compiled the tests of {}", exercise);
+            Ok(())
+        }
+        Err(output) => {
+            warn!(
+                "Compilation of {} failed!, Compiler error message:\n",
+                exercise
+            );
+            outln!("{}", output.stderr);
+            Err(VerifyFailed::from_output(
+                FailedType::Compilation,
+                output.stderr.clone(),
+                &output,
+            ))
+        }
+    }
+}
+
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), VerifyFailed> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_and_run(exercise: &Exercise, options: &RunOptions) -> Result<(), VerifyFailed> {
+    let progress_bar = spinner(format!("Compiling {exercise}..."));
 
-    let compilation_result = exercise.compile();
+    let compilation_result = exercise.compile(options);
     let compilation = match compilation_result {
         Ok(compilation) => compilation,
         Err(output) => {
@@ -47,11 +335,12 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), VerifyFailed> {
                 "Compilation of {} failed!, Compiler error message:\n",
                 exercise
             );
-            println!("{}", output.stderr);
-            return Err(VerifyFailed {
-                failed_type: FailedType::Compilation,
-                msg: String::from(output.stderr),
-            });
+            outln!("{}", output.stderr);
+            return Err(VerifyFailed::from_output(
+                FailedType::Compilation,
+                output.stderr.clone(),
+                &output,
+            ));
         }
     };
 
@@ -61,23 +350,123 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), VerifyFailed> {
 
     match result {
         Ok(output) => {
-            println!("{}", output.stdout);
+            outln!("{}", output.stdout);
+            if let Mode::Output = exercise.mode {
+                check_output(exercise, &output.stdout)?;
+            }
             success!("Successfully ran {}", exercise);
             Ok(())
         }
         Err(output) => {
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
+            outln!("{}", output.stdout);
+            outln!("{}", output.stderr);
+            if let Some(signal) = output.signal_description() {
+                outln!("{signal}");
+            }
 
             warn!("Ran {} with errors", exercise);
-            Err(VerifyFailed {
-                failed_type: FailedType::Running,
-                msg: String::from(
-                    format_args!("{} \n {}", output.stdout, output.stderr)
-                        .as_str()
-                        .unwrap(),
-                ),
-            })
+            Err(VerifyFailed::from_output(
+                FailedType::Running,
+                format!("{} \n {}", output.stdout, output.stderr),
+                &output,
+            ))
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::verify::error_codes;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_explain_error_code() {
+        let output = "error[E0382]: borrow of moved value: `v`\n";
+        let codes = error_codes(output);
+        assert_eq!(codes, ["E0382"]);
+
+        let explanation = explain_error_code(&codes[0]).unwrap();
+        assert!(explanation.contains("moved"));
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let output = concat!(
+            r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","#,
+            r#""spans":[{"file_name":"exercises/intro/intro2.rs","byte_start":25,"byte_end":28,"line_start":4,"line_end":4,"column_start":18,"column_end":21,"#,
+            r#""is_primary":true,"text":[],"label":"expected `i32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"#,
+            r#""children":[{"message":"try using a conversion method","code":null,"level":"help","spans":[{"file_name":"exercises/intro/intro2.rs","#,
+            r#""byte_start":25,"byte_end":28,"line_start":4,"line_end":4,"column_start":18,"column_end":21,"is_primary":true,"text":[],"label":null,"#,
+            r#""suggested_replacement":"\"a\".parse()","suggestion_applicability":"MaybeIncorrect","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0308]"}"#,
+            "\n",
+            r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting"}"#,
+            "\n",
+        );
+        let diagnostics = parse_diagnostics(output);
+        assert_eq!(diagnostics.len(), 1);
+        let error = &diagnostics[0];
+        assert_eq!(error.level, "error");
+        assert_eq!(error.code.as_deref(), Some("E0308"));
+        let span = &error.spans[0];
+        assert_eq!(span.file_name, "exercises/intro/intro2.rs");
+        assert_eq!((span.line_start, span.column_start), (4, 18));
+        assert_eq!((span.line_end, span.column_end), (4, 21));
+        assert!(span.is_primary);
+        assert_eq!(span.label.as_deref(), Some("expected `i32`, found `&str`"));
+        let help = &error.children[0];
+        assert_eq!(help.level, "help");
+        assert_eq!(
+            help.spans[0].suggested_replacement.as_deref(),
+            Some("\"a\".parse()")
+        );
+    }
+
+    #[test]
+    fn test_warmup_command() {
+        let (source, binary) = (Path::new("warmup.rs"), Path::new("warmup"));
+        let command = warmup_command(&Compiler::default(), source, binary);
+        assert_eq!(command.get_program(), "rustc");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["warmup.rs", "-o", "warmup"]);
+
+        let beta = Compiler::default().with_toolchain(Some("beta".into()));
+        let command = warmup_command(&beta, source, binary);
+        assert_eq!(command.get_program(), "rustup");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["run", "beta", "rustc", "warmup.rs", "-o", "warmup"]);
+    }
+
+    #[test]
+    fn test_warmup_skipped_when_warm() {
+        let temp_dir = std::env::temp_dir().join(format!("rustlings_warm_{}", std::process::id()));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let first = warmup_in(&Compiler::default(), &temp_dir);
+        let second = warmup_in(&Compiler::default(), &temp_dir);
+        let _ignored = fs::remove_dir_all(&temp_dir);
+        assert!(first.unwrap());
+        assert!(!second.unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_on_pass_env() {
+        let out = std::env::temp_dir().join(format!("rustlings_on_pass_{}", std::process::id()));
+        let exercise = Exercise {
+            name: "on_pass_exercise".into(),
+            path: PathBuf::from("exercises/on_pass_exercise.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let command = format!(
+            "echo \"$RUSTLINGS_EXERCISE $RUSTLINGS_EXERCISE_PATH\" > {}",
+            out.display()
+        );
+        on_pass(&command, &exercise).join().unwrap();
+
+        let written = fs::read_to_string(&out).unwrap();
+        let _ignored = fs::remove_file(&out);
+        assert_eq!(written, "on_pass_exercise exercises/on_pass_exercise.rs\n");
+    }
+}
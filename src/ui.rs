@@ -1,12 +1,110 @@
+use console::{style, Term};
+use indicatif::ProgressBar;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set by `--plain`
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+// Whether to print screen-reader-friendly output: plain sentences
+// instead of ASCII art, emoji and progress bars
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed) || env::var_os("RUSTLINGS_ACCESSIBLE").is_some()
+}
+
+// Set by `--color never`
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+pub fn set_no_color(no_color: bool) {
+    NO_COLOR.store(no_color, Ordering::Relaxed);
+}
+
+// Whether colors were turned off explicitly, rather than because the
+// output isn't a terminal
+pub fn no_color() -> bool {
+    NO_COLOR.load(Ordering::Relaxed)
+}
+
+pub fn no_emoji() -> bool {
+    env::var_os("NO_EMOJI").is_some() || is_plain()
+}
+
+thread_local! {
+    // The output collected by `capture` instead of being written
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Run `f`, collecting what it writes to stdout on this thread instead of
+// printing it, to print it later or not at all
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = CAPTURED.with(|captured| captured.replace(Some(String::new())));
+    let result = f();
+    let output = CAPTURED.with(|captured| captured.replace(outer));
+    (result, output.unwrap_or_default())
+}
+
+// Set once stdout was closed, to drop what is written to it from then on
+static STDOUT_CLOSED: AtomicBool = AtomicBool::new(false);
+
+// Write to stdout, dropping the output quietly once it was closed. Using
+// `println!` would panic when the output is piped into a command that stops
+// reading early, like `rustlings hint intro1 | head -n 1`. The command
+// still runs to the end, so that `rustlings verify | head` exits with the
+// status of the verification.
+pub fn write_stdout(line: &str) {
+    let captured = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(output) => {
+            output.push_str(line);
+            true
+        }
+        None => false,
+    });
+    if captured || STDOUT_CLOSED.load(Ordering::Relaxed) {
+        return;
+    }
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
+        match e.kind() {
+            io::ErrorKind::BrokenPipe => STDOUT_CLOSED.store(true, Ordering::Relaxed),
+            _ => std::process::exit(1),
+        };
+    });
+}
+
+// The text of captured output without its colors. Programs may write
+// bytes that aren't UTF-8, which are left out rather than failing on them.
+pub fn strip_colors(output: impl AsRef<[u8]>) -> String {
+    let output = output.as_ref();
+    let stripped = strip_ansi_escapes::strip(output).unwrap_or_else(|_| output.to_vec());
+    String::from_utf8_lossy(&stripped).into_owned()
+}
+
+// Like `println!`, but through `write_stdout`
+macro_rules! outln {
+    () => {
+        $crate::ui::write_stdout("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::ui::write_stdout(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
+
 macro_rules! warn {
     ($fmt:literal, $ex:expr) => {{
         use console::{style, Emoji};
-        use std::env;
         let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("!").red(), style(formatstr).red());
+        if $crate::ui::no_emoji() {
+            outln!("{} {}", style("!").red(), style(formatstr).red());
         } else {
-            println!(
+            outln!(
                 "{} {}",
                 style(Emoji("⚠️ ", "!")).red(),
                 style(formatstr).red()
@@ -18,12 +116,11 @@ macro_rules! warn {
 macro_rules! success {
     ($fmt:literal, $ex:expr) => {{
         use console::{style, Emoji};
-        use std::env;
         let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("✓").green(), style(formatstr).green());
+        if $crate::ui::no_emoji() {
+            outln!("{} {}", style("✓").green(), style(formatstr).green());
         } else {
-            println!(
+            outln!(
                 "{} {}",
                 style(Emoji("✅", "✓")).green(),
                 style(formatstr).green()
@@ -31,3 +128,206 @@ macro_rules! success {
         }
     }};
 }
+
+// A spinner showing what is being done while a compiler or an exercise
+// runs. It is drawn on stderr, so it's hidden when stderr isn't a
+// terminal or isn't colored (like with `--color never`), and in plain mode.
+pub fn spinner(message: String) -> ProgressBar {
+    let visible = Term::stderr().is_term() && console::colors_enabled_stderr();
+    spinner_on(message, visible)
+}
+
+fn spinner_on(message: String, visible: bool) -> ProgressBar {
+    if !visible || is_plain() {
+        return ProgressBar::hidden();
+    }
+    let progress_bar = ProgressBar::new_spinner();
+    progress_bar.set_message(message);
+    progress_bar.enable_steady_tick(100);
+    progress_bar
+}
+
+// The banner shown before the introduction, unless the manifest has its own
+pub fn print_welcome(custom: Option<&str>) {
+    match custom {
+        Some(message) => outln!("\n{message}\n"),
+        None if is_plain() => outln!("Welcome to rustlings.\n"),
+        None => outln!("\n{WELCOME}\n"),
+    }
+}
+
+// The message shown once every exercise is done, the manifest can
+// replace everything after the first line
+pub fn print_finished(custom: Option<&str>) {
+    if is_plain() {
+        outln!("All exercises completed.\n");
+    } else {
+        outln!(
+            "{emoji} All exercises completed! {emoji}",
+            emoji = console::Emoji("🎉", "★")
+        );
+    }
+    match custom {
+        Some(message) => outln!("\n{message}\n"),
+        None => {
+            if !is_plain() {
+                outln!("\n{FENISH_LINE}\n");
+            }
+            outln!("{FINISH_NOTES}\n");
+        }
+    }
+}
+
+// Print a value as pretty JSON, the format shared by every `--json`
+// flag so that the outputs of the commands stay consistent
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    write_stdout(&format!(
+        "{}\n",
+        serde_json::to_string_pretty(value).unwrap()
+    ));
+}
+
+// Print a hint, rendering its markdown when the output is colored
+// and printing it as written otherwise
+pub fn print_hint(hint: &str) {
+    if console::colors_enabled() {
+        write_stdout(&render_markdown(hint));
+    } else {
+        write_stdout(&format!("{hint}\n"));
+    }
+}
+
+// Render the markdown commonly found in hints for a terminal: fenced code
+// blocks are indented and colored, `inline code` and **bold** text are
+// highlighted, everything else (like bullet lists) is kept as is
+pub fn render_markdown(text: &str) -> String {
+    let mut rendered = String::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            let code = style(format!("    {line}")).cyan().force_styling(true);
+            rendered.push_str(&code.to_string());
+        } else {
+            rendered.push_str(&render_inline(line));
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
+
+// Highlight the `inline code` and **bold** spans of a line,
+// leaving unbalanced markers untouched
+fn render_inline(line: &str) -> String {
+    let mut rendered = String::new();
+    let mut rest = line;
+    loop {
+        let next = ["`", "**"]
+            .iter()
+            .filter_map(|marker| rest.find(marker).map(|start| (start, *marker)))
+            .min();
+        let Some((start, marker)) = next else {
+            break;
+        };
+        let after = &rest[start + marker.len()..];
+        let Some(len) = after.find(marker) else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let span = style(&after[..len]).force_styling(true);
+        let span = if marker == "`" {
+            span.yellow()
+        } else {
+            span.bold()
+        };
+        rendered.push_str(&span.to_string());
+        rest = &after[len + marker.len()..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+const FENISH_LINE: &str = r#"+----------------------------------------------------+
+|          You made it to the Fe-nish line!          |
++--------------------------  ------------------------+
+                          \\/
+     ▒▒          ▒▒▒▒▒▒▒▒      ▒▒▒▒▒▒▒▒          ▒▒
+   ▒▒▒▒  ▒▒    ▒▒        ▒▒  ▒▒        ▒▒    ▒▒  ▒▒▒▒
+   ▒▒▒▒  ▒▒  ▒▒            ▒▒            ▒▒  ▒▒  ▒▒▒▒
+ ░░▒▒▒▒░░▒▒  ▒▒            ▒▒            ▒▒  ▒▒░░▒▒▒▒
+   ▓▓▓▓▓▓▓▓  ▓▓      ▓▓██  ▓▓  ▓▓██      ▓▓  ▓▓▓▓▓▓▓▓
+     ▒▒▒▒    ▒▒      ████  ▒▒  ████      ▒▒░░  ▒▒▒▒
+       ▒▒  ▒▒▒▒▒▒        ▒▒▒▒▒▒        ▒▒▒▒▒▒  ▒▒
+         ▒▒▒▒▒▒▒▒▒▒▓▓▓▓▓▓▒▒▒▒▒▒▒▒▓▓▒▒▓▓▒▒▒▒▒▒▒▒
+           ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒
+             ▒▒▒▒▒▒▒▒▒▒██▒▒▒▒▒▒██▒▒▒▒▒▒▒▒▒▒
+           ▒▒  ▒▒▒▒▒▒▒▒▒▒██████▒▒▒▒▒▒▒▒▒▒  ▒▒
+         ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒
+       ▒▒    ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒    ▒▒
+       ▒▒  ▒▒    ▒▒                  ▒▒    ▒▒  ▒▒
+           ▒▒  ▒▒                      ▒▒  ▒▒"#;
+
+const FINISH_NOTES: &str = r#"We hope you enjoyed learning about the various aspects of Rust!
+If you noticed any issues, please don't hesitate to report them to our repo.
+You can also contribute your own exercises to help the greater community!
+
+Before reporting an issue or contributing, please read our guidelines:
+https://github.com/rust-lang/rustlings/blob/main/CONTRIBUTING.md"#;
+
+const WELCOME: &str = r#"       welcome to...
+                 _   _ _
+  _ __ _   _ ___| |_| (_)_ __   __ _ ___
+ | '__| | | / __| __| | | '_ \ / _` / __|
+ | |  | |_| \__ \ |_| | | | | | (_| \__ \
+ |_|   \__,_|___/\__|_|_|_| |_|\__, |___/
+                               |___/"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let (result, output) = capture(|| {
+            outln!("first");
+            let ((), inner) = capture(|| outln!("inner"));
+            outln!("last");
+            inner
+        });
+        assert_eq!(result, "inner\n");
+        assert_eq!(output, "first\nlast\n");
+    }
+
+    #[test]
+    fn test_strip_colors_of_invalid_utf8() {
+        assert_eq!(
+            strip_colors(b"\x1b[31merror\xff\xfe\x1b[0m: raw bytes"),
+            "error: raw bytes"
+        );
+    }
+
+    #[test]
+    fn test_spinner_hidden_without_terminal() {
+        let progress_bar = spinner_on("Compiling intro1...".to_string(), false);
+        assert!(progress_bar.is_hidden());
+        progress_bar.finish_and_clear();
+    }
+
+    #[test]
+    fn test_render_fenced_code_block() {
+        let hint = "Try this:\n```rust\nlet x = 5;\n```\n- use `x`";
+        assert_eq!(
+            render_markdown(hint),
+            "Try this:\n\x1b[36m    let x = 5;\x1b[0m\n- use \x1b[33mx\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_render_inline() {
+        assert_eq!(render_inline("a **b** c"), "a \x1b[1mb\x1b[0m c");
+        assert_eq!(render_inline("a `b c"), "a `b c");
+    }
+}
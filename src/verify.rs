@@ -1,22 +1,61 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::exercise::{CompiledExercise, Exercise, ExerciseOutput, Mode, RunOptions, State};
+use crate::ui::{self, spinner, strip_colors};
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+use serde::Serialize;
 use std::env;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 #[allow(dead_code)]
 pub enum FailedType {
     Compilation,
     Testing,
     Running,
     Clippy,
+    Output,
+    Signal,
     Unknown,
 }
 
-#[derive(Debug)]
+// Why an exercise failed, structured so that reports
+// don't have to parse the message again
+#[derive(Debug, Serialize)]
 pub struct VerifyFailed {
-    pub failed_type: FailedType,
+    // What kind of failure this is
+    pub kind: FailedType,
+    // The output explaining the failure
     pub msg: String,
+    // The rustc error codes (like E0382) found in the message
+    pub error_codes: Vec<String>,
+    // The exit code of the failed compiler or binary, if there was one
+    pub exit_code: Option<i32>,
+}
+
+impl VerifyFailed {
+    pub fn new(kind: FailedType, msg: String) -> Self {
+        VerifyFailed {
+            kind,
+            error_codes: error_codes(&msg),
+            msg,
+            exit_code: None,
+        }
+    }
+
+    // Build the failure of a compiler or binary that exited with errors.
+    // A binary killed by a signal rarely explains itself in its output,
+    // so the signal is reported in the message instead.
+    pub fn from_output(kind: FailedType, msg: String, output: &ExerciseOutput) -> Self {
+        let mut failed = match output.signal_description() {
+            Some(signal) => VerifyFailed::new(FailedType::Signal, format!("{msg}\n{signal}")),
+            None => VerifyFailed::new(kind, msg),
+        };
+        failed.exit_code = output.exit_code;
+        failed
+    }
 }
 
 pub struct ExerciseFailed<'a> {
@@ -24,6 +63,90 @@ pub struct ExerciseFailed<'a> {
     pub reason: VerifyFailed,
 }
 
+// Collect the rustc error codes (like E0382) found in the compiler output,
+// in the order they first appear
+pub fn error_codes(output: &str) -> Vec<String> {
+    let output = strip_colors(output);
+    let re = Regex::new(r"error\[(E\d{4})\]").unwrap();
+    let mut codes: Vec<String> = Vec::new();
+    for capture in re.captures_iter(&output) {
+        if !codes.iter().any(|code| code == &capture[1]) {
+            codes.push(capture[1].to_string());
+        }
+    }
+    codes
+}
+
+// How failures are reported besides the usual output
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReportFormat {
+    Plain,
+    // Workflow commands annotating the sources in GitHub Actions
+    Github,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(ReportFormat::Plain),
+            "github" => Ok(ReportFormat::Github),
+            _ => Err(format!(
+                "unknown format '{s}', expected \"plain\" or \"github\""
+            )),
+        }
+    }
+}
+
+// The GitHub Actions workflow commands marking the errors and warnings
+// of the compiler output at their location, like
+// `::error file=exercises/intro/intro2.rs,line=4,col=18::mismatched types`
+pub fn github_annotations(output: &str) -> Vec<String> {
+    // Commands end at a newline, and properties at a comma or a colon
+    let escape_message = |s: &str| {
+        s.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let escape_property = |s: &str| escape_message(s).replace(':', "%3A").replace(',', "%2C");
+
+    let output = strip_colors(output);
+    let header = Regex::new(r"^(error|warning)(?:\[E\d{4}\])?: (.*)$").unwrap();
+    let location = Regex::new(r"^\s*--> (.+):(\d+):(\d+)$").unwrap();
+    let mut annotations = Vec::new();
+    let mut pending = None;
+    for line in output.lines() {
+        if let Some(captures) = header.captures(line) {
+            pending = Some((captures[1].to_string(), captures[2].to_string()));
+        } else if let Some(captures) = location.captures(line) {
+            // Only the primary location of a message
+            if let Some((level, message)) = pending.take() {
+                annotations.push(format!(
+                    "::{level} file={},line={},col={}::{}",
+                    escape_property(&captures[1]),
+                    &captures[2],
+                    &captures[3],
+                    escape_message(&message)
+                ));
+            }
+        }
+    }
+    annotations
+}
+
+// Print the annotations of a failed compilation if they were asked for
+// and this runs in GitHub Actions, they would only be noise elsewhere
+pub fn annotate(format: ReportFormat, failed: &VerifyFailed) {
+    let in_actions = env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true");
+    if format != ReportFormat::Github || !in_actions || failed.kind != FailedType::Compilation {
+        return;
+    }
+    for annotation in github_annotations(&failed.msg) {
+        outln!("{annotation}");
+    }
+}
+
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
@@ -31,26 +154,40 @@ pub struct ExerciseFailed<'a> {
 // determines whether or not the test harness outputs are displayed.
 pub fn verify<'a>(
     exercise: &'a Exercise,
+    options: &RunOptions,
     progress: (usize, usize),
     verbose: bool,
 ) -> Result<(), ExerciseFailed<'a>> {
     let (num_done, total) = progress;
-    let bar = ProgressBar::new(total as u64);
-    bar.set_style(
-        ProgressStyle::default_bar()
-            .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
-            .progress_chars("#>-"),
-    );
+    let bar = if ui::is_plain() {
+        outln!("Progress: {num_done} of {total} exercises done.");
+        ProgressBar::hidden()
+    } else {
+        let bar = ProgressBar::new(total as u64);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
+                .progress_chars("#>-"),
+        );
+        bar
+    };
     bar.set_position(num_done as u64);
 
     let compile_result = match exercise.mode {
-        Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose),
-        Mode::Compile => compile_and_run_interactively(exercise),
-        Mode::Clippy => compile_only(exercise),
+        _ if exercise.runs_under_miri(options) => {
+            run_under_miri(exercise, options).map(|_| prompt_for_completion(exercise, None))
+        }
+        _ if exercise.check_command.is_some() => {
+            run_check_command(exercise, options).map(|_| prompt_for_completion(exercise, None))
+        }
+        Mode::Test => compile_and_test(exercise, options, RunMode::Interactive, verbose),
+        Mode::Compile | Mode::Output => compile_and_run_interactively(exercise, options),
+        Mode::Clippy => compile_only(exercise, options),
     };
 
     match compile_result {
         Err(e) => {
+            outln!("{}", try_again_hint(exercise));
             return Err(ExerciseFailed {
                 exercise,
                 reason: e,
@@ -59,10 +196,7 @@ pub fn verify<'a>(
         Ok(false) => {
             return Err(ExerciseFailed {
                 exercise,
-                reason: VerifyFailed {
-                    failed_type: FailedType::Unknown,
-                    msg: String::from(""),
-                },
+                reason: VerifyFailed::new(FailedType::Unknown, String::new()),
             });
         }
         _ => {}
@@ -77,36 +211,284 @@ pub fn verify<'a>(
     Ok(())
 }
 
+// Receives the progress of verifying exercises, so that front-ends can
+// present it their own way. The methods do nothing unless implemented.
+pub trait ProgressObserver {
+    // An exercise is about to be verified
+    fn on_start(&self, _exercise: &Exercise) {}
+    // An exercise passed after being verified for `elapsed`
+    fn on_pass(&self, _exercise: &Exercise, _elapsed: Duration) {}
+    // An exercise failed
+    fn on_fail(&self, _exercise: &Exercise, _reason: &VerifyFailed) {}
+}
+
+// An observer that may not be there, like the `--ipc` front-end
+impl<T: ProgressObserver + ?Sized> ProgressObserver for Option<&T> {
+    fn on_start(&self, exercise: &Exercise) {
+        if let Some(observer) = self {
+            observer.on_start(exercise);
+        }
+    }
+
+    fn on_pass(&self, exercise: &Exercise, elapsed: Duration) {
+        if let Some(observer) = self {
+            observer.on_pass(exercise, elapsed);
+        }
+    }
+
+    fn on_fail(&self, exercise: &Exercise, reason: &VerifyFailed) {
+        if let Some(observer) = self {
+            observer.on_fail(exercise, reason);
+        }
+    }
+}
+
+// Verify an exercise like `verify`, telling the observer how it went
+pub fn verify_observed<'a>(
+    exercise: &'a Exercise,
+    options: &RunOptions,
+    progress: (usize, usize),
+    verbose: bool,
+    observer: &dyn ProgressObserver,
+) -> Result<(), ExerciseFailed<'a>> {
+    observer.on_start(exercise);
+    let started = Instant::now();
+    let result = verify(exercise, options, progress, verbose);
+    match &result {
+        Ok(()) => observer.on_pass(exercise, started.elapsed()),
+        Err(failed) => observer.on_fail(exercise, &failed.reason),
+    }
+    result
+}
+
+// How to verify just the failed exercise again, worded after what
+// verifying it does
+pub fn try_again_hint(exercise: &Exercise) -> String {
+    let verb = match exercise.mode {
+        _ if exercise.check_command.is_some() => "check",
+        Mode::Test => "test",
+        Mode::Compile | Mode::Output => "compile and run",
+        Mode::Clippy => "check",
+    };
+    format!("To {verb} it again: rustlings run {}", exercise.name)
+}
+
+// The chapter of an exercise: its topic, or else the directory it is in
+pub fn chapter_of(exercise: &Exercise) -> String {
+    if let Some(topic) = &exercise.topic {
+        return topic.clone();
+    }
+    exercise
+        .path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .map_or_else(
+            || String::from("uncategorized"),
+            |dir| dir.to_string_lossy().into_owned(),
+        )
+}
+
+// The exercises grouped by chapter, the chapters in the order of their
+// first exercise
+pub fn chapters(exercises: &[Exercise]) -> Vec<(String, Vec<&Exercise>)> {
+    let mut chapters: Vec<(String, Vec<&Exercise>)> = Vec::new();
+    for exercise in exercises {
+        let chapter = chapter_of(exercise);
+        match chapters.iter_mut().find(|(name, _)| *name == chapter) {
+            Some((_, exercises)) => exercises.push(exercise),
+            None => chapters.push((chapter, vec![exercise])),
+        }
+    }
+    chapters
+}
+
+// The verification of an exercise of a chapter, with the output it printed
+pub struct ChapterResult<'a> {
+    pub name: &'a str,
+    pub passed: bool,
+    pub output: String,
+}
+
+// A single line for a chapter whose exercises all pass, otherwise a
+// line per exercise followed by the output of the failed ones
+pub fn render_chapter(chapter: &str, results: &[ChapterResult]) -> String {
+    let failed = results.iter().filter(|result| !result.passed).count();
+    if failed == 0 {
+        return format!(
+            "{}\n",
+            style(format!("chapter {chapter}: all passing")).green()
+        );
+    }
+    let summary = format!("chapter {chapter}: {failed} of {} failing", results.len());
+    let mut rendered = format!("{}\n", style(summary).red());
+    for result in results {
+        if result.passed {
+            rendered.push_str(&format!("  passed  {}\n", result.name));
+        } else {
+            rendered.push_str(&format!("  failed  {}\n{}", result.name, result.output));
+        }
+    }
+    rendered
+}
+
+// Whether the exercise compiles, runs (or passes its tests) and has been
+// marked as done, without printing anything along the way
+pub fn passes(exercise: &Exercise, options: &RunOptions) -> bool {
+    check_quietly(exercise, options).is_ok() && exercise.looks_done()
+}
+
+// Compile and run the given exercise like `run`, without printing anything
+pub fn check_quietly(exercise: &Exercise, options: &RunOptions) -> Result<(), VerifyFailed> {
+    if let Some(check_command) = &exercise.check_command {
+        return match exercise.run_check_command(check_command, options) {
+            Ok(_) => Ok(()),
+            Err(output) => Err(VerifyFailed::from_output(
+                FailedType::Running,
+                format!("{}\n{}", output.stdout, output.stderr),
+                &output,
+            )),
+        };
+    }
+    let compiled = exercise.compile(options).map_err(|output| {
+        VerifyFailed::from_output(FailedType::Compilation, output.stderr.clone(), &output)
+    })?;
+    if let Mode::Clippy = exercise.mode {
+        return Ok(());
+    }
+    let kind = match exercise.mode {
+        Mode::Test => FailedType::Testing,
+        _ => FailedType::Running,
+    };
+    let output = compiled.run().map_err(|output| {
+        let msg = format!("{}\n{}", output.stdout, output.stderr);
+        VerifyFailed::from_output(kind, msg, &output)
+    })?;
+    if let Mode::Output = exercise.mode {
+        if let Some(diff) = output_diff(exercise, &output.stdout) {
+            return Err(VerifyFailed::new(FailedType::Output, diff));
+        }
+    }
+    Ok(())
+}
+
+// The outcome of searching for the first failing exercise
+#[derive(PartialEq, Debug)]
+pub struct Bisection {
+    // Index of the first failing exercise, None if they all pass
+    pub first_failure: Option<usize>,
+    // How many exercises had to be compiled to find it
+    pub compilations: usize,
+    // Whether the search had to fall back to a linear scan
+    pub linear: bool,
+}
+
+// Find the first failing exercise with a binary search.
+// This assumes the exercises marked as done form a prefix of the list, so
+// that everything before the first failure passes and the first exercise
+// not marked as done is a failure. When the done markers don't form a
+// prefix, the search falls back to checking every exercise in order.
+pub fn bisect<T>(
+    exercises: &[T],
+    looks_done: impl Fn(&T) -> bool,
+    mut passes: impl FnMut(&T) -> bool,
+) -> Bisection {
+    let done_prefix = exercises
+        .iter()
+        .position(|e| !looks_done(e))
+        .unwrap_or(exercises.len());
+    let mut compilations = 0;
+
+    if exercises[done_prefix..].iter().any(&looks_done) {
+        let first_failure = exercises.iter().position(|e| {
+            compilations += 1;
+            !passes(e)
+        });
+        return Bisection {
+            first_failure,
+            compilations,
+            linear: true,
+        };
+    }
+
+    // An exercise that isn't done always fails, so the first
+    // failure is at most the first pending exercise
+    let (mut low, mut high) = (0, done_prefix);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        compilations += 1;
+        if passes(&exercises[mid]) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Bisection {
+        first_failure: (low < exercises.len()).then_some(low),
+        compilations,
+        linear: false,
+    }
+}
+
+// How long compiling and running each verified exercise took
+#[derive(Default, Serialize, Debug)]
+pub struct Timings {
+    // (exercise name, seconds) in the order they were verified
+    pub exercises: Vec<(String, f64)>,
+    // Wall time of the whole verification in seconds
+    pub total: f64,
+}
+
+impl Timings {
+    pub fn push(&mut self, name: &str, duration: Duration) {
+        self.exercises
+            .push((name.to_string(), duration.as_secs_f64()));
+    }
+
+    // The timings of the exercises, slowest first
+    pub fn slowest(&self) -> Vec<&(String, f64)> {
+        let mut sorted: Vec<_> = self.exercises.iter().collect();
+        sorted.sort_by(|a, b| b.1.total_cmp(&a.1));
+        sorted
+    }
+
+    pub fn print(&self) {
+        outln!("{:<30}\t{:>10}", "Exercise", "Time (s)");
+        for (name, seconds) in self.slowest() {
+            outln!("{name:<30}\t{seconds:>10.3}");
+        }
+        outln!("{:<30}\t{:>10.3}", "Total", self.total);
+    }
+}
+
 enum RunMode {
     Interactive,
     NonInteractive,
 }
 
 // Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), VerifyFailed> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose)?;
+pub fn test(exercise: &Exercise, options: &RunOptions, verbose: bool) -> Result<(), VerifyFailed> {
+    compile_and_test(exercise, options, RunMode::NonInteractive, verbose)?;
     Ok(())
 }
 
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise) -> Result<bool, VerifyFailed> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_only(exercise: &Exercise, options: &RunOptions) -> Result<bool, VerifyFailed> {
+    let progress_bar = spinner(format!("Compiling {exercise}..."));
 
-    let _ = compile(exercise, &progress_bar)?;
+    let _ = compile(exercise, options, &progress_bar)?;
     progress_bar.finish_and_clear();
 
     Ok(prompt_for_completion(exercise, None))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise) -> Result<bool, VerifyFailed> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_and_run_interactively(
+    exercise: &Exercise,
+    options: &RunOptions,
+) -> Result<bool, VerifyFailed> {
+    let progress_bar = spinner(format!("Compiling {exercise}..."));
 
-    let compilation = compile(exercise, &progress_bar)?;
+    let compilation = compile(exercise, options, &progress_bar)?;
 
     progress_bar.set_message(format!("Running {exercise}..."));
     let result = compilation.run();
@@ -116,41 +498,174 @@ fn compile_and_run_interactively(exercise: &Exercise) -> Result<bool, VerifyFail
         Ok(output) => output,
         Err(output) => {
             warn!("Ran {} with errors", exercise);
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-            return Err(VerifyFailed {
-                failed_type: FailedType::Running,
-                msg: String::from(
-                    format_args!("{} \n {}", output.stdout, output.stderr)
-                        .as_str()
-                        .unwrap(),
-                ),
-            });
+            outln!("{}", output.stdout);
+            outln!("{}", output.stderr);
+            if let Some(signal) = output.signal_description() {
+                outln!("{signal}");
+            }
+            return Err(VerifyFailed::from_output(
+                FailedType::Running,
+                format!("{} \n {}", output.stdout, output.stderr),
+                &output,
+            ));
         }
     };
 
+    if let Mode::Output = exercise.mode {
+        check_output(exercise, &output.stdout)?;
+    }
+
     Ok(prompt_for_completion(exercise, Some(output.stdout)))
 }
 
+// Run the exercise (or its tests) under Miri, failing when it finds
+// undefined behavior or the exercise doesn't compile or run
+pub fn run_under_miri(exercise: &Exercise, options: &RunOptions) -> Result<(), VerifyFailed> {
+    let progress_bar = spinner(format!("Running {exercise} under Miri..."));
+    let result = exercise.run_miri(options);
+    progress_bar.finish_and_clear();
+
+    let output = match result {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Failed to run Miri on {}", exercise);
+            outln!("{e}");
+            return Err(VerifyFailed::new(FailedType::Running, e.to_string()));
+        }
+    };
+    if output.exit_code == Some(0) {
+        outln!("{}", output.stdout);
+        return Ok(());
+    }
+    if output.stderr.contains("Undefined Behavior") {
+        warn!(
+            "Miri found undefined behavior in {}! Here's the output:",
+            exercise
+        );
+    } else {
+        warn!("Running {} under Miri failed! Here's the output:", exercise);
+    }
+    outln!("{}", output.stdout);
+    outln!("{}", output.stderr);
+    Err(VerifyFailed::from_output(
+        FailedType::Running,
+        output.stderr.clone(),
+        &output,
+    ))
+}
+
+// Check the exercise with its `check_command` instead of compiling it
+pub fn run_check_command(exercise: &Exercise, options: &RunOptions) -> Result<(), VerifyFailed> {
+    let check_command = exercise.check_command.as_deref().unwrap_or_default();
+    let progress_bar = spinner(format!("Checking {exercise}..."));
+    let result = exercise.run_check_command(check_command, options);
+    progress_bar.finish_and_clear();
+
+    match result {
+        Ok(output) => {
+            outln!("{}", output.stdout);
+            Ok(())
+        }
+        Err(output) => {
+            warn!("Checking {} failed! Here's the output:", exercise);
+            outln!("{}", output.stdout);
+            outln!("{}", output.stderr);
+            Err(VerifyFailed::from_output(
+                FailedType::Running,
+                format!("{}\n{}", output.stdout, output.stderr),
+                &output,
+            ))
+        }
+    }
+}
+
+// Normalize program output for comparison, ignoring
+// line endings and surrounding whitespace
+fn normalize_output(output: &str) -> Vec<String> {
+    output
+        .trim()
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+// Describe how the output differs from the exercise's expected output
+// line by line, or None if it matches
+fn output_diff(exercise: &Exercise, output: &str) -> Option<String> {
+    let expected = match exercise.expected_output() {
+        Some(expected) => expected,
+        None => {
+            return Some(String::from(
+                "The exercise has no expected output to compare with",
+            ))
+        }
+    };
+    let expected = normalize_output(&expected);
+    let actual = normalize_output(output);
+    if expected == actual {
+        return None;
+    }
+
+    let mut diff = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let (expected_line, actual_line) = (expected.get(i), actual.get(i));
+        if expected_line == actual_line {
+            continue;
+        }
+        diff.push_str(&format!("line {}:\n", i + 1));
+        if let Some(line) = expected_line {
+            diff.push_str(&format!("- {line}\n"));
+        }
+        if let Some(line) = actual_line {
+            diff.push_str(&format!("+ {line}\n"));
+        }
+    }
+    Some(diff)
+}
+
+// Compare the output of an exercise in output mode with its expected output
+pub fn check_output(exercise: &Exercise, output: &str) -> Result<(), VerifyFailed> {
+    match output_diff(exercise, output) {
+        None => Ok(()),
+        Some(diff) => {
+            warn!(
+                "The output of {} doesn't match the expected output (- expected, + actual):",
+                exercise
+            );
+            outln!("{diff}");
+            Err(VerifyFailed::new(FailedType::Output, diff))
+        }
+    }
+}
+
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
 fn compile_and_test(
     exercise: &Exercise,
+    options: &RunOptions,
     run_mode: RunMode,
     verbose: bool,
 ) -> Result<bool, VerifyFailed> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = spinner(format!("Testing {exercise}..."));
 
-    let compilation = compile(exercise, &progress_bar)?;
+    let compilation = compile(exercise, options, &progress_bar)?;
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     match result {
         Ok(output) => {
             if verbose {
-                println!("{}", output.stdout);
+                outln!("{}", output.stdout);
+            }
+            if let Some(filter) = &options.test_filter {
+                if output.stdout.contains("running 0 tests") {
+                    let msg = format!(
+                        "No test of {} has a name containing '{filter}'",
+                        exercise.name
+                    );
+                    warn!("{}", msg);
+                    return Err(VerifyFailed::new(FailedType::Testing, msg));
+                }
             }
             if let RunMode::Interactive = run_mode {
                 Ok(prompt_for_completion(exercise, None))
@@ -163,22 +678,27 @@ fn compile_and_test(
                 "Testing of {} failed! Please try again. Here's the output:",
                 exercise
             );
-            println!("{}", output.stdout);
-            Err(VerifyFailed {
-                failed_type: FailedType::Testing,
-                msg: output.stderr,
-            })
+            outln!("{}", output.stdout);
+            if let Some(signal) = output.signal_description() {
+                outln!("{signal}");
+            }
+            Err(VerifyFailed::from_output(
+                FailedType::Testing,
+                output.stderr.clone(),
+                &output,
+            ))
         }
     }
 }
 
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
+fn compile<'a>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
+    options: &'a RunOptions,
+    progress_bar: &ProgressBar,
 ) -> Result<CompiledExercise<'a>, VerifyFailed> {
-    let compilation_result = exercise.compile();
+    let compilation_result = exercise.compile(options);
 
     match compilation_result {
         Ok(compilation) => Ok(compilation),
@@ -188,11 +708,12 @@ fn compile<'a, 'b>(
                 "Compiling of {} failed! Please try again. Here's the output:",
                 exercise
             );
-            println!("{}", output.stderr);
-            Err(VerifyFailed {
-                failed_type: FailedType::Compilation,
-                msg: String::from(output.stderr),
-            })
+            outln!("{}", output.stderr);
+            Err(VerifyFailed::from_output(
+                FailedType::Compilation,
+                output.stderr.clone(),
+                &output,
+            ))
         }
     }
 }
@@ -204,12 +725,12 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
     };
 
     match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
+        Mode::Compile | Mode::Output => success!("Successfully ran {}!", exercise),
         Mode::Test => success!("Successfully tested {}!", exercise),
         Mode::Clippy => success!("Successfully compiled {}!", exercise),
     }
 
-    let no_emoji = env::var("NO_EMOJI").is_ok();
+    let no_emoji = ui::no_emoji();
 
     let clippy_success_msg = if no_emoji {
         "The code is compiling, and Clippy is happy!"
@@ -221,30 +742,33 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
         Mode::Compile => "The code is compiling!",
         Mode::Test => "The code is compiling, and the tests pass!",
         Mode::Clippy => clippy_success_msg,
+        Mode::Output => "The code is compiling, and the output is correct!",
     };
 
-    println!();
-    if no_emoji {
-        println!("~*~ {success_msg} ~*~")
+    outln!();
+    if ui::is_plain() {
+        outln!("{success_msg}")
+    } else if no_emoji {
+        outln!("~*~ {success_msg} ~*~")
     } else {
-        println!("🎉 🎉  {success_msg} 🎉 🎉")
+        outln!("🎉 🎉  {success_msg} 🎉 🎉")
     }
-    println!();
+    outln!();
 
     if let Some(output) = prompt_output {
-        println!("Output:");
-        println!("{}", separator());
-        println!("{output}");
-        println!("{}", separator());
-        println!();
+        outln!("Output:");
+        outln!("{}", separator());
+        outln!("{output}");
+        outln!("{}", separator());
+        outln!();
     }
 
-    println!("You can keep working on this exercise,");
-    println!(
+    outln!("You can keep working on this exercise,");
+    outln!(
         "or jump into the next one by removing the {} comment:",
         style("`I AM NOT DONE`").bold()
     );
-    println!();
+    outln!();
     for context_line in context {
         let formatted_line = if context_line.important {
             format!("{}", style(context_line.line).bold())
@@ -252,7 +776,7 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
             context_line.line.to_string()
         };
 
-        println!(
+        outln!(
             "{:>2} {}  {}",
             style(context_line.number).blue().bold(),
             style("|").blue(),
@@ -266,3 +790,272 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
 fn separator() -> console::StyledObject<&'static str> {
     style("====================").bold()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl ProgressObserver for RecordingObserver {
+        fn on_start(&self, exercise: &Exercise) {
+            self.events
+                .borrow_mut()
+                .push(format!("start {}", exercise.name));
+        }
+
+        fn on_pass(&self, exercise: &Exercise, _elapsed: Duration) {
+            self.events
+                .borrow_mut()
+                .push(format!("pass {}", exercise.name));
+        }
+
+        fn on_fail(&self, exercise: &Exercise, reason: &VerifyFailed) {
+            self.events
+                .borrow_mut()
+                .push(format!("fail {} {:?}", exercise.name, reason.kind));
+        }
+    }
+
+    #[test]
+    fn test_observer_sees_progress_in_order() {
+        let exercises = [
+            Exercise {
+                name: "compSuccess".into(),
+                path: "tests/fixture/success/compSuccess.rs".into(),
+                mode: Mode::Compile,
+                ..Default::default()
+            },
+            Exercise {
+                name: "compFailure".into(),
+                path: "tests/fixture/failure/compFailure.rs".into(),
+                mode: Mode::Compile,
+                ..Default::default()
+            },
+        ];
+        let observer = RecordingObserver::default();
+        for (done, exercise) in exercises.iter().enumerate() {
+            let _ = verify_observed(
+                exercise,
+                &RunOptions::default(),
+                (done, exercises.len()),
+                false,
+                &observer,
+            );
+        }
+        assert_eq!(
+            *observer.events.borrow(),
+            [
+                "start compSuccess",
+                "pass compSuccess",
+                "start compFailure",
+                "fail compFailure Compilation",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_miri_keeps_output_comparison() {
+        let exercise = Exercise {
+            name: "outputSuccess".into(),
+            path: "tests/fixture/success/outputSuccess.rs".into(),
+            mode: Mode::Output,
+            expected_output: Some("Something else".into()),
+            ..Default::default()
+        };
+        let options = RunOptions {
+            miri: true,
+            ..Default::default()
+        };
+        let failed = verify(&exercise, &options, (0, 1), false).err().unwrap();
+        assert_eq!(failed.reason.kind, FailedType::Output);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_quietly_runs_check_command() {
+        // Not Rust, which only the check command can check
+        let exercise = |check_command: &str| Exercise {
+            name: "notRust".into(),
+            path: "tests/fixture/state/info.toml".into(),
+            mode: Mode::Compile,
+            check_command: Some(check_command.into()),
+            ..Default::default()
+        };
+        assert!(check_quietly(
+            &exercise("grep -q exercises {path}"),
+            &RunOptions::default()
+        )
+        .is_ok());
+        let failed = check_quietly(
+            &exercise("grep -q 'not there' {path}"),
+            &RunOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(failed.kind, FailedType::Running);
+    }
+
+    #[test]
+    fn test_timings_slowest_first() {
+        let mut timings = Timings::default();
+        timings.push("intro1", Duration::from_millis(200));
+        timings.push("intro2", Duration::from_millis(1500));
+        timings.push("variables1", Duration::from_millis(700));
+
+        assert_eq!(timings.exercises[0], ("intro1".to_string(), 0.2));
+        let names: Vec<_> = timings
+            .slowest()
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, ["intro2", "variables1", "intro1"]);
+    }
+
+    // Exercises are modeled as (looks done, passes) pairs
+    fn linear(exercises: &[(bool, bool)]) -> Option<usize> {
+        exercises
+            .iter()
+            .position(|&(done, passes)| !(done && passes))
+    }
+
+    #[test]
+    fn test_error_codes_extracted() {
+        let output = "\x1b[0m\x1b[1m\x1b[38;5;9merror[E0382]\x1b[0m: borrow of moved value\n\
+                      error[E0308]: mismatched types\n\
+                      error[E0382]: borrow of moved value\n\
+                      error: aborting due to 3 previous errors\n";
+        let failed = VerifyFailed::new(FailedType::Compilation, output.to_string());
+        assert_eq!(failed.kind, FailedType::Compilation);
+        assert_eq!(failed.error_codes, ["E0382", "E0308"]);
+        assert_eq!(failed.exit_code, None);
+    }
+
+    #[test]
+    fn test_bisect_matches_linear() {
+        let mut exercises = vec![(true, true); 20];
+        exercises.extend([(false, true); 5]);
+        for exercise in &mut exercises[13..20] {
+            exercise.1 = false;
+        }
+
+        let bisection = bisect(&exercises, |e| e.0, |e| e.0 && e.1);
+        assert_eq!(bisection.first_failure, linear(&exercises));
+        assert_eq!(bisection.first_failure, Some(13));
+        assert!(!bisection.linear);
+        assert!(bisection.compilations < 13);
+    }
+
+    #[test]
+    fn test_bisect_all_passing() {
+        let exercises = vec![(true, true); 8];
+        let bisection = bisect(&exercises, |e| e.0, |e| e.0 && e.1);
+        assert_eq!(bisection.first_failure, None);
+    }
+
+    #[test]
+    fn test_bisect_falls_back_to_linear() {
+        let mut exercises = vec![(true, true); 10];
+        exercises[3] = (false, true);
+
+        let bisection = bisect(&exercises, |e| e.0, |e| e.0 && e.1);
+        assert_eq!(bisection.first_failure, linear(&exercises));
+        assert!(bisection.linear);
+        assert_eq!(bisection.compilations, 4);
+    }
+
+    #[test]
+    fn test_try_again_hint() {
+        let mut exercise = Exercise {
+            name: "tests1".into(),
+            mode: Mode::Test,
+            ..Default::default()
+        };
+        assert_eq!(
+            try_again_hint(&exercise),
+            "To test it again: rustlings run tests1"
+        );
+        exercise.name = "intro1".into();
+        exercise.mode = Mode::Compile;
+        assert_eq!(
+            try_again_hint(&exercise),
+            "To compile and run it again: rustlings run intro1"
+        );
+    }
+
+    #[test]
+    fn test_render_chapters() {
+        let exercise = |path: &str, topic: Option<&str>| Exercise {
+            name: path.rsplit('/').next().unwrap().into(),
+            path: path.into(),
+            topic: topic.map(String::from),
+            ..Default::default()
+        };
+        let exercises = [
+            exercise("exercises/intro/intro1.rs", None),
+            exercise("exercises/variables/variables1.rs", None),
+            exercise("exercises/intro/intro2.rs", None),
+            exercise("exercises/quiz1.rs", Some("quizzes")),
+        ];
+        let chapters = chapters(&exercises);
+        let names: Vec<_> = chapters.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["intro", "variables", "quizzes"]);
+        assert_eq!(chapters[0].1.len(), 2);
+
+        let passing = render_chapter(
+            "intro",
+            &[
+                ChapterResult {
+                    name: "intro1",
+                    passed: true,
+                    output: "Compiled intro1\n".into(),
+                },
+                ChapterResult {
+                    name: "intro2",
+                    passed: true,
+                    output: String::new(),
+                },
+            ],
+        );
+        assert_eq!(passing.lines().count(), 1);
+        assert!(passing.contains("chapter intro: all passing"));
+
+        let mixed = render_chapter(
+            "variables",
+            &[
+                ChapterResult {
+                    name: "variables1",
+                    passed: true,
+                    output: String::new(),
+                },
+                ChapterResult {
+                    name: "variables2",
+                    passed: false,
+                    output: "error[E0425]: cannot find value `x`\n".into(),
+                },
+            ],
+        );
+        assert!(mixed.contains("chapter variables: 1 of 2 failing"));
+        assert!(mixed.contains("  passed  variables1\n"));
+        assert!(mixed.contains("  failed  variables2\nerror[E0425]: cannot find value `x`\n"));
+    }
+
+    #[test]
+    fn test_github_annotations() {
+        let output = "\x1b[1merror[E0308]\x1b[0m: mismatched types\n \
+                      --> exercises/intro/intro2.rs:4:18\n  |\n\
+                      4 |     let x: i32 = \"a\";\n  |            ---   ^^^ expected `i32`, found `&str`\n\
+                      \nwarning: unused variable: `y`\n --> exercises/intro/intro2.rs:5:9\n\
+                      \nerror: aborting due to 1 previous error; 1 warning emitted\n";
+        assert_eq!(
+            github_annotations(output),
+            [
+                "::error file=exercises/intro/intro2.rs,line=4,col=18::mismatched types",
+                "::warning file=exercises/intro/intro2.rs,line=5,col=9::unused variable: `y`",
+            ]
+        );
+        assert!(github_annotations("error: linking failed, 50% done\n").is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+// I AM NOT DONE
+
+fn add_one(n: i32) -> i32 {
+    n + ???
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_one() {
+        assert_eq!(add_one(1), 2);
+    }
+}
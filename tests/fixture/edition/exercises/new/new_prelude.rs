@@ -0,0 +1,6 @@
+// `TryFrom` is only in the prelude from the 2021 edition on
+
+fn main() {
+    let small = u8::try_from(5u32).unwrap();
+    println!("{}", small);
+}
@@ -0,0 +1,6 @@
+// `async` is only a keyword from the 2018 edition on
+
+fn main() {
+    let async = 1;
+    println!("{}", async);
+}
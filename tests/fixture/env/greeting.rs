@@ -0,0 +1,7 @@
+use std::env;
+
+fn main() {
+    let greeting = env::var("RUSTLINGS_GREETING").unwrap();
+    let path = if env::var_os("PATH").is_some() { "set" } else { "unset" };
+    println!("{greeting}, PATH is {path}");
+}
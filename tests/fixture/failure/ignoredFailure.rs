@@ -0,0 +1,10 @@
+#[test]
+fn passing() {
+    assert!(true);
+}
+
+#[test]
+#[ignore]
+fn not_enabled_yet() {
+    assert!(false);
+}
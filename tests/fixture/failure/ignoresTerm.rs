@@ -0,0 +1,14 @@
+extern "C" {
+    fn signal(signum: i32, handler: usize) -> usize;
+}
+
+fn main() {
+    // SIG_IGN for SIGTERM
+    unsafe {
+        signal(15, 1);
+    }
+    println!("Ignoring SIGTERM");
+    loop {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,5 @@
+// fake_solution
+
+fn main() {
+    println!("Solved!");
+}
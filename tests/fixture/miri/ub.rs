@@ -0,0 +1,8 @@
+fn main() {
+    let numbers = vec![1, 2, 3];
+    let first = numbers.as_ptr();
+    drop(numbers);
+    // Reading freed memory happens to work when compiled normally
+    let value = unsafe { *first };
+    println!("{value}");
+}
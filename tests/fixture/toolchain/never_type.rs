@@ -0,0 +1,9 @@
+#![feature(never_type)]
+
+fn diverge() -> ! {
+    panic!("never returns");
+}
+
+fn main() {
+    let _never: fn() -> ! = diverge;
+}
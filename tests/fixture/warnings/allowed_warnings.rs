@@ -0,0 +1,5 @@
+// This exercise warns about dead code on purpose
+
+fn unused() {}
+
+fn main() {}
@@ -3,7 +3,9 @@ use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
 use std::fs::File;
 use std::io::Read;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
 
 #[test]
 fn runs_without_arguments() {
@@ -44,7 +46,7 @@ fn verify_fails_if_some_fails() {
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
+        .args(["run", "compSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -54,7 +56,7 @@ fn run_single_compile_success() {
 fn run_single_compile_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
+        .args(["run", "compFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -64,17 +66,131 @@ fn run_single_compile_failure() {
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
 }
 
+#[test]
+fn run_single_test_filtered() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "testSuccess", "--test", "pass"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("test passing ... ok"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testSuccess", "--test", "missing"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "No test of testSuccess has a name containing 'missing'",
+        ));
+}
+
+#[test]
+fn run_no_run_only_compiles_tests() {
+    let dir = std::env::temp_dir().join(format!("rustlings_no_run_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"slow\"\npath = \"slow.rs\"\nmode = \"test\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("slow.rs"),
+        "#[test]\nfn not_passing() {\n    assert!(false);\n}\n",
+    )
+    .unwrap();
+
+    let with_tests = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "slow"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let compiled_only = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "slow", "--no-run"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(with_tests.status.code(), Some(1));
+    assert!(compiled_only.status.success());
+    let stdout = String::from_utf8_lossy(&compiled_only.stdout);
+    assert!(stdout.contains("Successfully compiled the tests of"));
+    assert!(!stdout.contains("not_passing"));
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testFailure", "--no-run"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Compilation of"));
+}
+
+#[test]
+fn verify_writes_logs_to_output_dir() {
+    let dir = std::env::temp_dir().join(format!("rustlings_logs_{}", std::process::id()));
+    let _ignored = std::fs::remove_dir_all(&dir);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--fail-fast", "false", "--output-dir"])
+        .arg(dir.join("logs"))
+        .current_dir("tests/fixture/grading")
+        .assert()
+        .code(1);
+    let passing = std::fs::read_to_string(dir.join("logs/passing.log"));
+    let failing = std::fs::read_to_string(dir.join("logs/failing.log"));
+    let _ignored = std::fs::remove_dir_all(&dir);
+    assert!(passing.is_ok());
+    let failing = failing.unwrap();
+    assert!(failing.contains("Compiling of failing.rs failed!"));
+    assert!(failing.contains("expected expression"));
+    assert!(!failing.contains('\x1b'));
+}
+
+#[test]
+fn run_suggests_closest_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSucess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .code(1)
+        .stdout("No exercise found for 'compSucess'!\nDid you mean 'compSuccess'?\n");
+}
+
+#[test]
+fn verify_summary_only() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--summary-only", "--fail-fast", "false"])
+        .current_dir("tests/fixture/grading")
+        .assert()
+        .code(1)
+        .stdout("1/2 passed\n");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--summary-only"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout("3/3 passed\n");
+}
+
 #[test]
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
+        .args(["run", "testFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -84,7 +200,7 @@ fn run_single_test_failure() {
 fn run_single_test_not_passed() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
+        .args(["run", "testNotPassed.rs"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -104,7 +220,7 @@ fn run_single_test_no_filename() {
 fn run_single_test_no_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
+        .args(["run", "compNoExercise.rs"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(1);
@@ -114,7 +230,7 @@ fn run_single_test_no_exercise() {
 fn reset_single_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["reset", "intro1"])
+        .args(["reset", "intro1"])
         .assert()
         .code(0);
 }
@@ -135,7 +251,7 @@ fn reset_no_exercise() {
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
+        .args(["hint", "testFailure"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(0)
@@ -171,7 +287,7 @@ fn all_exercises_require_confirmation() {
 fn run_compile_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
+        .args(["run", "pending_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -182,7 +298,7 @@ fn run_compile_exercise_does_not_prompt() {
 fn run_test_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
+        .args(["run", "pending_test_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -193,7 +309,7 @@ fn run_test_exercise_does_not_prompt() {
 fn run_single_test_success_with_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
+        .args(["--nocapture", "run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -204,7 +320,7 @@ fn run_single_test_success_with_output() {
 fn run_single_test_success_without_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -215,7 +331,7 @@ fn run_single_test_success_without_output() {
 fn run_rustlings_list() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success();
@@ -225,7 +341,7 @@ fn run_rustlings_list() {
 fn run_rustlings_list_no_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success()
@@ -236,18 +352,69 @@ fn run_rustlings_list_no_pending() {
 fn run_rustlings_list_both_done_and_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").and(predicates::str::contains("Pending")));
 }
 
+#[test]
+fn run_rustlings_list_rows_and_progress() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir("tests/fixture/state")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<(&str, &str)> = stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split('\t').map(str::trim);
+            Some((columns.next()?, columns.nth(1)?))
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("pending_exercise", "Pending"),
+            ("pending_test_exercise", "Pending"),
+            ("finished_exercise", "Done"),
+        ]
+    );
+    assert!(stdout.ends_with("Progress: You completed 1 / 3 exercises (33.3 %).\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_rustlings_list_stops_quietly_when_piped_into_head() {
+    use std::io::{BufRead, BufReader};
+
+    let mut child = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names"])
+        .current_dir("tests/fixture/state")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut first = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut first)
+        .unwrap();
+    let status = child.wait().unwrap();
+
+    assert_eq!(first, "pending_exercise\n");
+    assert!(status.success());
+}
+
 #[test]
 fn run_rustlings_list_without_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
+        .args(["list", "--solved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -258,9 +425,1908 @@ fn run_rustlings_list_without_pending() {
 fn run_rustlings_list_without_done() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
+        .args(["list", "--unsolved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[cfg(unix)]
+#[test]
+fn run_single_compile_killed_by_signal() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "runAbort"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "process killed by signal 6 (SIGABRT)",
+        ));
+}
+
+// A running rustlings whose output is read as it is printed, so that
+// tests wait for the line they expect rather than for a fixed time
+struct LiveProcess {
+    child: std::process::Child,
+    lines: std::sync::mpsc::Receiver<String>,
+    stdout: String,
+}
+
+impl LiveProcess {
+    fn spawn(dir: impl AsRef<std::path::Path>, args: &[&str]) -> Self {
+        use std::io::BufRead;
+
+        let mut child = Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(args)
+            .current_dir(dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+        let (tx, lines) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout.split(b'\n').map_while(Result::ok) {
+                if tx
+                    .send(String::from_utf8_lossy(&line).into_owned())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        LiveProcess {
+            child,
+            lines,
+            stdout: String::new(),
+        }
+    }
+
+    // Read the output until a line contains `text`, false if it doesn't
+    // show up within a minute or the output ends first
+    fn wait_for(&mut self, text: &str) -> bool {
+        let deadline = std::time::Instant::now() + Duration::from_secs(60);
+        loop {
+            let left = deadline.saturating_duration_since(std::time::Instant::now());
+            match self.lines.recv_timeout(left) {
+                Ok(line) => {
+                    self.stdout.push_str(&line);
+                    self.stdout.push('\n');
+                    if line.contains(text) {
+                        return true;
+                    }
+                }
+                Err(_) => return false,
+            }
+        }
+    }
+
+    fn send(&mut self, input: &str) {
+        use std::io::Write;
+
+        let stdin = self.child.stdin.as_mut().unwrap();
+        stdin.write_all(input.as_bytes()).unwrap();
+        stdin.flush().unwrap();
+    }
+
+    // Wait for the process to exit, killing it after a minute, and return
+    // its status along with all of its output
+    fn finish(mut self) -> (std::process::ExitStatus, String) {
+        let deadline = std::time::Instant::now() + Duration::from_secs(60);
+        while self.child.try_wait().unwrap().is_none() {
+            if std::time::Instant::now() >= deadline {
+                let _ignored = self.child.kill();
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        let status = self.child.wait().unwrap();
+        for line in self.lines.iter() {
+            self.stdout.push_str(&line);
+            self.stdout.push('\n');
+        }
+        (status, self.stdout)
+    }
+
+    // Quit watch mode through its shell
+    fn quit(mut self) -> (std::process::ExitStatus, String) {
+        self.send("quit\n");
+        self.finish()
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_quits_cleanly_on_interrupt() {
+    let mut watch = LiveProcess::spawn("tests/fixture/watch", &["watch"]);
+    assert!(watch.wait_for("Welcome to watch mode!"));
+    Command::new("kill")
+        .args(["-INT", &watch.child.id().to_string()])
+        .status()
+        .unwrap();
+
+    let (status, stdout) = watch.finish();
+    assert!(status.success());
+    assert!(stdout.contains("you can simply run `rustlings watch` again"));
+}
+
+#[test]
+fn watch_reports_exercise_for_review() {
+    use std::io::Write;
+
+    let dir = std::env::temp_dir().join(format!("rustlings_review_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    for file in ["info.toml", "exercises/pending_watch_exercise.rs"] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/watch").join(file),
+            dir.join(file),
+        )
+        .unwrap();
+    }
+
+    let mut child = Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("watch")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"report The hint is empty\nquit\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let review = std::fs::read_to_string(dir.join("review.jsonl")).unwrap_or_default();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains("Reported pending_watch_exercise in review.jsonl"));
+    let entry: serde_json::Value = serde_json::from_str(review.trim()).unwrap();
+    assert_eq!(entry["exercise"], "pending_watch_exercise");
+    assert_eq!(entry["message"], "The hint is empty");
+}
+
+#[test]
+fn watch_survives_invalid_utf8_output() {
+    let dir = std::env::temp_dir().join(format!("rustlings_utf8_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"raw\"\npath = \"exercises/raw.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("exercises/raw.rs"),
+        "use std::io::Write;\n\nfn main() {\n    \
+         std::io::stderr().write_all(b\"raw \\xff\\xfe bytes\\n\").unwrap();\n    \
+         std::process::exit(1);\n}\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--once", "--keep-going"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    // Failing the exercise, rather than panicking with exit code 101
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("raw \u{FFFD}\u{FFFD} bytes"));
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_history_logs_saved_attempts() {
+    let dir = std::env::temp_dir().join(format!("rustlings_history_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    for file in ["info.toml", "exercises/pending_watch_exercise.rs"] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/watch").join(file),
+            dir.join(file),
+        )
+        .unwrap();
+    }
+
+    let mut watch = LiveProcess::spawn(&dir, &["watch", "--no-clear"]);
+    assert!(watch.wait_for("Welcome to watch mode!"));
+    std::fs::write(
+        dir.join("exercises/pending_watch_exercise.rs"),
+        "// I AM NOT DONE\n\nfn main() {}\n",
+    )
+    .unwrap();
+    assert!(watch.wait_for("You can keep working on this exercise"));
+    let (status, _) = watch.quit();
+    let history = std::fs::read_to_string(dir.join("history.jsonl")).unwrap_or_default();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    // The save is logged, the verification when watch mode started isn't
+    let entries: Vec<serde_json::Value> = history
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["exercise"], "pending_watch_exercise");
+    assert_eq!(entries[0]["passed"], false);
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_on_pass_runs_when_exercises_turn_to_passing() {
+    let dir = std::env::temp_dir().join(format!("rustlings_on_pass_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    let mut info = String::new();
+    for name in ["fixed", "pending", "later"] {
+        info.push_str(&format!(
+            "[[exercises]]\nname = \"{name}\"\npath = \"exercises/{name}.rs\"\nmode = \"compile\"\nhint = \"\"\n\n"
+        ));
+    }
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    // Solved since it failed in an earlier session
+    std::fs::write(dir.join("exercises/fixed.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(
+        dir.join("history.jsonl"),
+        "{\"exercise\":\"fixed\",\"timestamp\":1,\"passed\":false}\n",
+    )
+    .unwrap();
+    for name in ["pending", "later"] {
+        std::fs::write(
+            dir.join(format!("exercises/{name}.rs")),
+            "// I AM NOT DONE\n\nfn main() {}\n",
+        )
+        .unwrap();
+    }
+
+    let mut watch = LiveProcess::spawn(
+        &dir,
+        &[
+            "watch",
+            "--no-clear",
+            "--on-pass",
+            "echo $RUSTLINGS_EXERCISE >> passed.txt",
+        ],
+    );
+    assert!(watch.wait_for("Welcome to watch mode!"));
+    // Passing, then passing again without turning to passing
+    for content in ["fn main() {}\n", "fn main() {\n}\n"] {
+        std::fs::write(dir.join("exercises/pending.rs"), content).unwrap();
+        assert!(watch.wait_for("You can keep working on this exercise"));
+    }
+    let (status, _) = watch.quit();
+    // The commands run in the background
+    let mut passed = String::new();
+    for _ in 0..50 {
+        passed = std::fs::read_to_string(dir.join("passed.txt")).unwrap_or_default();
+        if passed.lines().count() >= 2 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    assert_eq!(passed, "fixed\npending\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_milestones_count_verified_exercises() {
+    let dir = std::env::temp_dir().join(format!("rustlings_milestones_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"broken\"\npath = \"exercises/broken.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join(".rustlings-milestones.json"), "[]").unwrap();
+    let exercise = dir.join("exercises/broken.rs");
+    std::fs::write(
+        &exercise,
+        "// I AM NOT DONE\n\nfn main() {\n    println!(\"{x}\");\n}\n",
+    )
+    .unwrap();
+
+    let mut watch = LiveProcess::spawn(&dir, &["watch", "--no-clear"]);
+    assert!(watch.wait_for("Welcome to watch mode!"));
+    // Removing the marker doesn't solve the exercise
+    std::fs::write(&exercise, "fn main() {\n    println!(\"{x}\");\n}\n").unwrap();
+    assert!(watch.wait_for("Compiling of exercises/broken.rs failed!"));
+    let unsolved = watch.stdout.clone();
+    std::fs::write(
+        &exercise,
+        "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n",
+    )
+    .unwrap();
+    assert!(watch.wait_for("You solved your first exercise"));
+    let (status, _) = watch.finish();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    assert!(!unsolved.contains("You solved your first exercise"));
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_verifies_when_extra_paths_change() {
+    let dir = std::env::temp_dir().join(format!("rustlings_watch_extra_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::create_dir_all(dir.join("shared")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"uses_helper\"\npath = \"exercises/uses_helper.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("exercises/uses_helper.rs"),
+        "include!(\"../shared/helper.rs\");\n\nfn main() {\n    helper();\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("shared/helper.rs"), "").unwrap();
+
+    let mut watch = LiveProcess::spawn(&dir, &["watch", "--no-clear", "--watch-extra", "shared"]);
+    assert!(watch.wait_for("Welcome to watch mode!"));
+    // Only the shared file changes, which fixes the exercise
+    std::fs::write(dir.join("shared/helper.rs"), "fn helper() {}\n").unwrap();
+    let (status, stdout) = watch.finish();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    assert!(stdout.contains("We hope you enjoyed learning about the various aspects of Rust!"));
+}
+
+#[test]
+fn watch_interval_verify_is_not_an_attempt() {
+    let dir = std::env::temp_dir().join(format!("rustlings_interval_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    for file in ["info.toml", "exercises/pending_watch_exercise.rs"] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/watch").join(file),
+            dir.join(file),
+        )
+        .unwrap();
+    }
+
+    let mut watch = LiveProcess::spawn(
+        &dir,
+        &[
+            "watch",
+            "--no-clear",
+            "--keep-going",
+            "--watch-interval-verify",
+            "1",
+        ],
+    );
+    assert!(watch.wait_for("You can keep working on this exercise"));
+    thread::sleep(Duration::from_secs(3));
+    let (status, stdout) = watch.quit();
+    let history = std::fs::read_to_string(dir.join("history.jsonl")).unwrap_or_default();
+    let data = std::fs::read_to_string(dir.join("data.jsonl")).unwrap_or_default();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    // The unchanged failure isn't printed again
+    assert_eq!(
+        stdout
+            .matches("You can keep working on this exercise")
+            .count(),
+        1
+    );
+    // Only the failure when watch mode started is recorded, not as an
+    // attempt in the history
+    assert_eq!(history, "");
+    assert_eq!(data.lines().count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_interval_verify_reports_a_change() {
+    let dir =
+        std::env::temp_dir().join(format!("rustlings_interval_change_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::create_dir_all(dir.join("shared")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"uses_helper\"\npath = \"exercises/uses_helper.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("exercises/uses_helper.rs"),
+        "include!(\"../shared/helper.rs\");\n\nfn main() {\n    helper();\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("shared/helper.rs"), "").unwrap();
+
+    let mut watch = LiveProcess::spawn(
+        &dir,
+        &["watch", "--no-clear", "--watch-interval-verify", "1"],
+    );
+    assert!(watch.wait_for("Welcome to watch mode!"));
+    thread::sleep(Duration::from_secs(2));
+    // Watch mode doesn't watch this file, only the interval notices it
+    std::fs::write(dir.join("shared/helper.rs"), "fn helper() {}\n").unwrap();
+    let (status, stdout) = watch.finish();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    assert_eq!(stdout.matches("cannot find function `helper`").count(), 1);
+    assert!(stdout.contains("We hope you enjoyed learning about the various aspects of Rust!"));
+}
+
+#[test]
+fn list_explains_order() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--explain-order"])
+        .current_dir("tests/fixture/order")
+        .assert()
+        .success()
+        .stdout(
+            "third            \tnumber 1 in the order of the manifest\n\
+             first            \tnumber 2 in the order of the manifest\n\
+             second           \tmanifest order, after the exercises of the order\n\
+             fourth           \tmanifest order, after the exercises of the order, requires second, first\n",
+        );
+}
+
+#[test]
+fn stats_and_history_as_json() {
+    let dir = std::env::temp_dir().join(format!("rustlings_stats_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy("tests/fixture/stats/info.toml", dir.join("info.toml")).unwrap();
+    std::fs::copy("tests/fixture/stats/data.jsonl", dir.join("data.jsonl")).unwrap();
+    std::fs::copy(
+        "tests/fixture/stats/history.jsonl",
+        dir.join("history.jsonl"),
+    )
+    .unwrap();
+    let json = |args: &[&str]| -> serde_json::Value {
+        let output = Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let hints = json(&["stats", "--json"]);
+    let topics = json(&["stats", "--per-topic", "--json"]);
+    let history = json(&["history", "--json", "-n", "2"]);
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(
+        hints,
+        serde_json::json!([{ "exercise": "intro1", "hints_used": 1 }])
+    );
+    assert_eq!(
+        topics,
+        serde_json::json!([
+            {
+                "topic": "structs",
+                "exercises": 2,
+                "effort": { "attempts": 5, "seconds": 320 }
+            },
+            {
+                "topic": "intro",
+                "exercises": 1,
+                "effort": { "attempts": 1, "seconds": 80 }
+            }
+        ])
+    );
+    assert_eq!(
+        history,
+        serde_json::json!([
+            { "exercise": "intro1", "timestamp": 1080, "passed": true },
+            { "exercise": "structs1", "timestamp": 2300, "passed": true }
+        ])
+    );
+}
+
+#[test]
+fn progress_round_trips_through_archive() {
+    let root = std::env::temp_dir().join(format!("rustlings_progress_{}", std::process::id()));
+    let manifest = "[[exercises]]\nname = \"intro1\"\npath = \"exercises/intro1.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+                    [[exercises]]\nname = \"intro2\"\npath = \"exercises/intro2.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+                    [[exercises]]\nname = \"intro3\"\npath = \"exercises/intro3.rs\"\nmode = \"compile\"\nhint = \"\"\n";
+    let starter = "// I AM NOT DONE\nfn main() {}\n";
+    let solution = |n: usize| format!("fn main() {{\n    println!(\"solved {n}\");\n}}\n");
+    let checkout = |name: &str, files: [String; 3]| {
+        let dir = root.join(name);
+        std::fs::create_dir_all(dir.join("exercises")).unwrap();
+        std::fs::write(dir.join("info.toml"), manifest).unwrap();
+        for (i, content) in files.iter().enumerate() {
+            std::fs::write(dir.join(format!("exercises/intro{}.rs", i + 1)), content).unwrap();
+        }
+        dir
+    };
+    let old = checkout("old", [solution(1), solution(2), starter.to_string()]);
+    let new = checkout(
+        "new",
+        [
+            starter.to_string(),
+            "fn main() {}\n".to_string(),
+            starter.to_string(),
+        ],
+    );
+    let archive = root.join("progress.tar.gz");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("export-progress")
+        .arg(&archive)
+        .current_dir(&old)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Saved 2 solved exercises"));
+    let imported = Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("import-progress")
+        .arg(&archive)
+        .current_dir(&new)
+        .output()
+        .unwrap();
+    let read =
+        |n: usize| std::fs::read_to_string(new.join(format!("exercises/intro{n}.rs"))).unwrap();
+    let (first, second, third) = (read(1), read(2), read(3));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["import-progress", "--force"])
+        .arg(&archive)
+        .current_dir(&new)
+        .assert()
+        .success();
+    let forced = read(2);
+    let _ignored = std::fs::remove_dir_all(&root);
+
+    assert!(imported.status.success());
+    let stdout = String::from_utf8_lossy(&imported.stdout);
+    assert!(stdout.contains("intro2 is solved differently here, kept this solution"));
+    assert!(stdout.contains("Restored 1 solved exercises"));
+    assert_eq!(first, solution(1));
+    assert_eq!(second, "fn main() {}\n");
+    assert_eq!(third, starter);
+    assert_eq!(forced, solution(2));
+}
+
+#[test]
+fn run_rustlings_list_json_modified() {
+    let dir = std::env::temp_dir().join(format!("rustlings_modified_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in [
+        "info.toml",
+        "pending_exercise.rs",
+        "pending_test_exercise.rs",
+        "finished_exercise.rs",
+    ] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/state").join(file),
+            dir.join(file),
+        )
+        .unwrap();
+    }
+    let modified = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    File::options()
+        .write(true)
+        .open(dir.join("finished_exercise.rs"))
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--json", "--sort", "mtime"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+    assert!(output.status.success());
+
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    let last = entries.last().unwrap();
+    assert_eq!(last["name"], "finished_exercise");
+    assert_eq!(last["status"], "Done");
+    assert_eq!(last["modified"], 1_000_000_000);
+}
+
+#[test]
+fn verify_bisect_finds_first_failure() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--bisect"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The first failing exercise is compFailure",
+        ));
+}
+
+#[test]
+fn verify_bisect_all_success() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--bisect"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("All exercises pass!"));
+}
+
+#[test]
+fn run_single_output_success() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "outputSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_output_mismatch() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "outputFailure"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("- Output!\n+ World!"));
+}
+
+#[test]
+fn get_hint_from_hint_file() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "hint_file_exercise"])
+        .current_dir("tests/fixture/hint")
+        .assert()
+        .code(0)
+        .stdout("This hint lives in its own file.\n\nIt can span several paragraphs.\n");
+}
+
+#[test]
+fn about_describes_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["about", "hint_file_exercise"])
+        .current_dir("tests/fixture/hint")
+        .assert()
+        .success()
+        .stdout(
+            "About hint_file_exercise:\n\n\
+             Hints that don't fit in the manifest can live in their own file.\n\n\
+             You will learn:\n  \
+             - Moving long texts out of info.toml\n  \
+             - Writing hints in Markdown\n\n\
+             Read more in the Rust book: \
+             https://doc.rust-lang.org/book/ch14-02-publishing-to-crates-io.html\n",
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["about", "compSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "compSuccess has no description yet",
+        ));
+}
+
+#[test]
+fn watch_once_fails_on_pending_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--once"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("pending_exercise.rs"));
+}
+
+#[test]
+fn watch_writes_status_file() {
+    let path = std::env::temp_dir().join(format!("rustlings_status_{}.json", std::process::id()));
+    let _ignored = std::fs::remove_file(&path);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--once", "--status-file"])
+        .arg(&path)
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1);
+    let status = std::fs::read_to_string(&path).unwrap();
+    let _ignored = std::fs::remove_file(&path);
+    let status: serde_json::Value = serde_json::from_str(&status).unwrap();
+    assert_eq!(status["exercise"], "pending_exercise");
+    // Watch stops at the first failure, verifying none of the others
+    assert_eq!(status["done"], 0);
+    assert_eq!(status["total"], 3);
+    assert!(status["timestamp"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn watch_once_all_success() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--once"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("All exercises completed!"));
+}
+
+#[test]
+fn watch_without_exercises_dir() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("watch")
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::is_match(
+            "exercises directory not found at .*tests/fixture/state/exercises; are you in the rustlings folder\\?\n",
+        ).unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_does_not_clear_when_piped() {
+    let mut watch = LiveProcess::spawn("tests/fixture/watch", &["watch"]);
+    assert!(watch.wait_for("Welcome to watch mode!"));
+
+    let (status, stdout) = watch.quit();
+    assert!(status.success());
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+fn run_rustlings_list_glob_and_plain_filter() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--filter", "intro/*,variables1"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "intro1\nintro2\nvariables1\nProgress:",
+        ));
+}
+
+#[test]
+fn option_values_after_equals_sign() {
+    let list = |args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let spaced = list(&["list", "--names", "--filter", "intro/*,variables1"]);
+    let joined = list(&["list", "--names", "--filter=intro/*,variables1"]);
+    assert!(joined.status.success());
+    assert_eq!(joined.stdout, spaced.stdout);
+
+    // Switches take no value
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names=true"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn warns_when_exercises_need_newer_version() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSuccess"])
+        .current_dir("tests/fixture/version")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "These exercises need rustlings v99.0.0 or newer",
+        ));
+}
+
+#[test]
+fn version_warning_keeps_json_valid() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--json"])
+        .current_dir("tests/fixture/version")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(list[0]["name"], "compSuccess");
+}
+
+// The writing end of a pipe whose reading end is already closed, like the
+// output piped into `head` once it exited
+#[cfg(unix)]
+fn closed_pipe() -> Stdio {
+    use std::os::fd::FromRawFd;
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    unsafe {
+        libc::close(fds[0]);
+        Stdio::from_raw_fd(fds[1])
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn get_hint_into_closed_pipe() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "testFailure"])
+        .current_dir("tests/fixture/failure")
+        .stdout(closed_pipe())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("panicked"));
+}
+
+#[cfg(unix)]
+#[test]
+fn verify_into_closed_pipe_keeps_failure_status() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/failure")
+        .stdout(closed_pipe())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("panicked"));
+}
+
+#[test]
+fn run_rustlings_list_by_tag() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--tag", "ownership", "--tag", "traits"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "pending_exercise\nfinished_exercise\nProgress:",
+        ));
+}
+
+#[test]
+fn verify_dry_run_prints_plan() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--dry-run"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .success()
+        .stdout(
+            "Would verify 4 exercises:\n\
+             compFailure      \tcompFailure.rs\n\
+             testFailure      \ttestFailure.rs\n\
+             runAbort         \trunAbort.rs\n\
+             outputFailure    \toutputFailure.rs\n",
+        );
+}
+
+#[test]
+fn get_hint_as_json() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "hint_file_exercise", "--json"])
+        .current_dir("tests/fixture/hint")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let hint: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(hint["name"], "hint_file_exercise");
+    assert_eq!(
+        hint["hint"],
+        "This hint lives in its own file.\n\nIt can span several paragraphs."
+    );
+    assert_eq!(hint["has_solution"], true);
+}
+
+#[test]
+fn seed_data_backfills_solved_exercises() {
+    let data_path = "tests/fixture/seed/data.jsonl";
+    let _ignored = std::fs::remove_file(data_path);
+    for _ in 0..2 {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("seed-data")
+            .current_dir("tests/fixture/seed")
+            .assert()
+            .success();
+    }
+
+    let data = std::fs::read_to_string(data_path).unwrap();
+    let paths: Vec<String> = data
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            record["path"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(paths, ["compSuccess.rs", "finished_exercise.rs"]);
+}
+
+#[test]
+fn verify_with_directory_editions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/edition")
+        .assert()
+        .success();
+}
+
+#[test]
+fn verify_fail_fast_stops_at_first_failure() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "1 of 1 verified exercises failed: compFailure\n",
+        ));
+}
+
+#[test]
+fn verify_without_fail_fast_lists_all_failures() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--fail-fast=false"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "4 of 4 verified exercises failed: compFailure, testFailure, runAbort, outputFailure\n",
+        ));
+}
+
+#[test]
+fn run_bless_writes_golden_output() {
+    let dir = std::env::temp_dir().join(format!("rustlings_bless_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"greeting\"\npath = \"greeting.rs\"\nmode = \"output\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("greeting.rs"),
+        "fn main() {\n    println!(\"Hello,\");\n    println!(\"golden!\");\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greeting", "--bless"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let golden = std::fs::read_to_string(dir.join("greeting.out"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir(&dir)
+        .assert()
+        .success();
+
+    let _ignored = std::fs::remove_dir_all(&dir);
+    assert_eq!(golden.unwrap(), "Hello,\ngolden!\n");
+}
+
+#[test]
+fn plain_mode_has_no_art_or_emoji() {
+    let plain_outputs = [
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["--plain", "watch", "--once"])
+            .current_dir("tests/fixture/success")
+            .output()
+            .unwrap(),
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["watch", "--once"])
+            .env("RUSTLINGS_ACCESSIBLE", "1")
+            .current_dir("tests/fixture/success")
+            .output()
+            .unwrap(),
+    ];
+    for output in plain_outputs {
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("All exercises completed.\n"));
+        assert!(!stdout.contains("+---"));
+        // Box drawing, block elements and emoji
+        assert!(!stdout
+            .chars()
+            .any(|c| ('\u{2500}'..='\u{259f}').contains(&c) || c >= '\u{1f300}'));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn run_watch_reruns_until_passing() {
+    let dir = std::env::temp_dir().join(format!("rustlings_run_watch_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"focus\"\npath = \"exercises/focus.rs\"\nmode = \"compile\"\nhint = \"Declare x first\"\n",
+    )
+    .unwrap();
+    let exercise = dir.join("exercises/focus.rs");
+    std::fs::write(&exercise, "fn main() {\n    println!(\"{x}\");\n}\n").unwrap();
+
+    let mut run = LiveProcess::spawn(&dir, &["run", "focus", "--watch"]);
+    assert!(run.wait_for("Waiting for exercises/focus.rs to be saved..."));
+    std::fs::write(
+        &exercise,
+        "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n",
+    )
+    .unwrap();
+
+    let (status, stdout) = run.finish();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    assert!(stdout.contains("Hint:\nDeclare x first\n"));
+    assert!(stdout.contains("Successfully ran"));
+}
+
+#[cfg(unix)]
+#[test]
+fn verify_watches_first_failure() {
+    let dir = std::env::temp_dir().join(format!("rustlings_watch_on_fail_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"broken\"\npath = \"exercises/broken.rs\"\nmode = \"compile\"\nhint = \"Declare x first\"\n\n\
+         [[exercises]]\nname = \"after\"\npath = \"exercises/after.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    let exercise = dir.join("exercises/broken.rs");
+    std::fs::write(&exercise, "fn main() {\n    println!(\"{x}\");\n}\n").unwrap();
+    std::fs::write(dir.join("exercises/after.rs"), "fn main() {}\n").unwrap();
+
+    let mut verify = LiveProcess::spawn(&dir, &["verify", "--watch-on-fail", "--timings"]);
+    assert!(verify.wait_for("Waiting for exercises/broken.rs to be saved..."));
+    std::fs::write(
+        &exercise,
+        "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n",
+    )
+    .unwrap();
+
+    let (status, stdout) = verify.finish();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    assert!(
+        stdout.contains("Watching exercises/broken.rs until it passes, then verifying the rest.")
+    );
+    assert!(stdout.contains("Hint:\nDeclare x first\n"));
+    // The rest is verified once the failing exercise passes
+    assert!(stdout.lines().any(|line| line.starts_with("after ")));
+}
+
+#[cfg(unix)]
+#[test]
+fn verify_interrupted_after_watch_on_fail() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let dir = std::env::temp_dir().join(format!("rustlings_interrupt_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"broken\"\npath = \"exercises/broken.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"slow\"\npath = \"exercises/slow.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    let exercise = dir.join("exercises/broken.rs");
+    std::fs::write(&exercise, "fn main() {\n    println!(\"{x}\");\n}\n").unwrap();
+    std::fs::write(
+        dir.join("exercises/slow.rs"),
+        "fn main() {\n    std::thread::sleep(std::time::Duration::from_secs(20));\n}\n",
+    )
+    .unwrap();
+
+    let mut verify = LiveProcess::spawn(&dir, &["--plain", "verify", "--watch-on-fail"]);
+    assert!(verify.wait_for("Waiting for exercises/broken.rs to be saved..."));
+    std::fs::write(&exercise, "fn main() {}\n").unwrap();
+    assert!(verify.wait_for("Successfully ran exercises/broken.rs"));
+    // Verifying the rest goes on, and Ctrl-C stops it again
+    assert!(verify.wait_for("Progress: 0 of 2 exercises done."));
+    assert_eq!(
+        unsafe { libc::kill(verify.child.id() as i32, libc::SIGINT) },
+        0
+    );
+    let (status, _) = verify.finish();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(status.signal(), Some(libc::SIGINT));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_with_check_command() {
+    let dir = std::env::temp_dir().join(format!("rustlings_check_command_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"greeting\"\npath = \"exercises/greeting.rs\"\nmode = \"compile\"\n\
+         check_command = \"grep -q 'Hello, world' {path}\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    let exercise = dir.join("exercises/greeting.rs");
+    let run = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["run", "greeting"])
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+    // Not valid Rust, only the command decides
+    std::fs::write(&exercise, "Goodbye\n").unwrap();
+    let failing = run();
+    std::fs::write(&exercise, "Hello, world\n").unwrap();
+    let passing = run();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(!failing.status.success());
+    assert!(
+        String::from_utf8_lossy(&failing.stdout).contains("Checking exercises/greeting.rs failed!")
+    );
+    assert!(passing.status.success());
+    assert!(String::from_utf8_lossy(&passing.stdout)
+        .contains("Successfully checked exercises/greeting.rs"));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_with_compiler_wrapper() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("rustlings_compiler_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let shim = dir.join("shim");
+    let log = dir.join("invocations");
+    std::fs::write(
+        &shim,
+        format!("#!/bin/sh\necho \"$@\" >> {}\nexec \"$@\"\n", log.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("--compiler")
+        .arg(format!("{} rustc", shim.display()))
+        .args(["run", "testSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success();
+    let invocations = std::fs::read_to_string(&log).unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(invocations.starts_with("rustc --test testSuccess.rs -o "));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_inherits_rustflags() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("rustlings_rustflags_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let shim = dir.join("shim");
+    let log = dir.join("invocations");
+    std::fs::write(
+        &shim,
+        format!("#!/bin/sh\necho \"$@\" >> {}\nexec \"$@\"\n", log.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let run = |inherit: bool| {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command
+            .arg("--compiler")
+            .arg(format!("{} rustc", shim.display()))
+            .env("RUSTFLAGS", "-C opt-level=1  -C debuginfo=0");
+        if !inherit {
+            command.arg("--no-inherit-flags");
+        }
+        command
+            .args(["run", "testSuccess"])
+            .current_dir("tests/fixture/success/")
+            .assert()
+            .success();
+    };
+    run(true);
+    run(false);
+    let invocations = std::fs::read_to_string(&log).unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    let mut invocations = invocations.lines();
+    assert!(invocations
+        .next()
+        .unwrap()
+        .starts_with("rustc -C opt-level=1 -C debuginfo=0 --test testSuccess.rs -o "));
+    assert!(invocations
+        .next()
+        .unwrap()
+        .starts_with("rustc --test testSuccess.rs -o "));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_with_toolchain() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("rustlings_toolchain_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Stands in for rustup, running the command on the current toolchain
+    let shim = dir.join("rustup");
+    let log = dir.join("invocations");
+    std::fs::write(
+        &shim,
+        format!(
+            "#!/bin/sh\necho \"$@\" >> {}\nshift 2\nexec \"$@\"\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap());
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--toolchain", "beta", "run", "testSuccess"])
+        .env("PATH", path)
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success();
+    let invocations = std::fs::read_to_string(&log).unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    let mut invocations = invocations.lines();
+    assert_eq!(invocations.next(), Some("run beta rustc --version"));
+    assert!(invocations
+        .next()
+        .unwrap()
+        .starts_with("run beta rustc --test testSuccess.rs -o "));
+}
+
+#[cfg(unix)]
+#[test]
+fn verify_skips_nightly_exercise_on_stable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sysroot = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .unwrap()
+        .stdout;
+    let rustc = std::path::Path::new(String::from_utf8(sysroot).unwrap().trim()).join("bin/rustc");
+    let dir = std::env::temp_dir().join(format!("rustlings_stable_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let shim = dir.join("rustc");
+    std::fs::write(
+        &shim,
+        format!(
+            "#!/bin/sh\n\
+             if [ \"$1\" = \"-vV\" ]; then\n\
+             \tprintf 'rustc 1.95.0\\nrelease: 1.95.0\\nhost: x86_64-unknown-linux-gnu\\n'\n\
+             else\n\
+             \texec {} \"$@\"\n\
+             fi\n",
+            rustc.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap());
+
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .env("PATH", path)
+        .current_dir("tests/fixture/toolchain")
+        .assert();
+    let _ignored = std::fs::remove_dir_all(&dir);
+    assert.success().stdout(predicates::str::contains(
+        "never_type skipped: requires nightly",
+    ));
+}
+
+#[test]
+fn run_with_exercise_env() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greeting"])
+        .env("RUSTLINGS_GREETING", "Hello from the shell")
+        .current_dir("tests/fixture/env")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Hello from the manifest, PATH is set",
+        ));
+}
+
+#[test]
+fn reset_to_solution() {
+    let dir = std::env::temp_dir().join(format!("rustlings_reset_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("exercises/intro")).unwrap();
+    std::fs::create_dir_all(dir.join("solutions/intro")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"intro1\"\npath = \"exercises/intro/intro1.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    let exercise = dir.join("exercises/intro/intro1.rs");
+    std::fs::write(&exercise, "// I AM NOT DONE\n").unwrap();
+    let solution = "fn main() {\n    println!(\"solved\");\n}\n";
+    std::fs::write(dir.join("solutions/intro/intro1.rs"), solution).unwrap();
+    let reset = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["reset", "intro1", "--to", "solution"])
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args([
+                "-c",
+                "user.name=rustlings",
+                "-c",
+                "user.email=rustlings@example.com",
+            ])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+
+    // Without git, the changes can't be kept
+    let refused = reset();
+    let untouched = std::fs::read_to_string(&exercise).unwrap();
+    git(&["init", "--quiet"]);
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "starter"]);
+    let work = "// I AM NOT DONE\nfn main() {\n    // half done\n}\n";
+    std::fs::write(&exercise, work).unwrap();
+    let reset_output = reset();
+    let reset_to = std::fs::read_to_string(&exercise).unwrap();
+    git(&["checkout", "--quiet", "--", "exercises/intro/intro1.rs"]);
+    git(&["stash", "pop", "--quiet"]);
+    let restored = std::fs::read_to_string(&exercise).unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(refused.status.code(), Some(1));
+    assert_eq!(untouched, "// I AM NOT DONE\n");
+    assert!(reset_output.status.success());
+    assert!(String::from_utf8_lossy(&reset_output.stdout)
+        .contains("now contains the reference solution"));
+    assert_eq!(reset_to, solution);
+    assert_eq!(restored, work);
+}
+
+#[test]
+fn reset_to_missing_solution() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "pending_exercise", "--to=solution"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("has no solution to reset it to"));
+}
+
+#[test]
+fn no_spinner_without_terminal() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn ignored_exercises_are_skipped() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names"])
+        .current_dir("tests/fixture/ignored")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("published\nProgress:"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/ignored")
+        .assert()
+        .success();
+}
+
+#[test]
+fn ignored_exercises_are_included_on_request() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--include-ignored"])
+        .current_dir("tests/fixture/ignored")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("published\ndraft\nProgress:"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--include-ignored"])
+        .current_dir("tests/fixture/ignored")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "1 of 2 verified exercises failed: draft\n",
+        ));
+}
+
+#[test]
+fn custom_final_message() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--once"])
+        .current_dir("tests/fixture/messages")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "\nWell done, the whole course is finished.\n",
+        ))
+        .stdout(predicates::str::contains("Fe-nish line").not());
+}
+
+#[test]
+fn custom_welcome_and_default_messages() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .current_dir("tests/fixture/messages")
+        .assert()
+        .success()
+        .stdout(
+            "\nWelcome to the Rust for embedded course!\n\n\
+             Run `rustlings watch` to start the course.\n\n",
+        );
+}
+
+#[test]
+fn check_exercise_ready() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-exercise", "ready"])
+        .current_dir("tests/fixture/authoring")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("ready looks ready to submit"));
+}
+
+#[test]
+fn check_exercise_reports_every_problem() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-exercise", "untested"])
+        .current_dir("tests/fixture/authoring")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "Found 3 problems with untested:\n  \
+             - Its hint is empty\n  \
+             - Its file has no `I AM NOT DONE` marker\n  \
+             - It is in test mode but its file has no #[test] function\n",
+        ));
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-exercise", "unfinished"])
+        .current_dir("tests/fixture/authoring")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "Found 3 problems with unfinished:",
+        ))
+        .stdout(predicates::str::contains(
+            "  - Its file missing.rs can't be read",
+        ))
+        .stdout(predicates::str::contains(
+            "  - Its mode 'run' isn't one of compile, test, clippy or output\n",
+        ))
+        .stdout(predicates::str::contains("  - Its hint is empty\n"));
+}
+
+#[test]
+fn check_exercise_unknown() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-exercise", "nope"])
+        .current_dir("tests/fixture/authoring")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercise found for 'nope'\n"));
+}
+
+#[test]
+fn verify_only_named_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--only=runAbort,compFailure", "--fail-fast=false"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "2 of 2 verified exercises failed: compFailure, runAbort\n",
+        ));
+}
+
+#[test]
+fn verify_only_reports_unknown_names() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--only", "compFailure,nope,runabort"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "No exercise found for: nope, runabort\n",
+        ))
+        .stdout(predicates::str::contains("Compiling").not());
+}
+
+#[test]
+fn color_always_colors_piped_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--color", "always", "run", "compFailure"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\x1b["));
+}
+
+#[test]
+fn color_auto_keeps_compiler_colors() {
+    // Only rustlings' own output goes uncolored when it isn't a terminal
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compFailure"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\x1b["));
+}
+
+#[test]
+fn color_never_suppresses_colors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--color=never", "run", "compFailure"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\x1b").not());
+}
+
+#[test]
+fn run_single_compile_failure_json() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compFailure", "--json"])
+        .current_dir("tests/fixture/failure")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["name"], "compFailure");
+    assert_eq!(report["status"], "failed");
+    assert_eq!(report["kind"], "compilation");
+    assert_eq!(report["exit_code"], 1);
+    assert!(report["error"].as_str().unwrap().contains("error"));
+    assert!(report["elapsed_ms"].is_u64());
+}
+
+#[test]
+fn run_rustlings_list_next() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--next"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout("pending_exercise\n");
+}
+
+#[test]
+fn run_rustlings_list_next_all_done() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--next", "--paths"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn list_exercises_of_tar_bundle() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "--bundle",
+            "tests/fixture/bundle/exercises.tar.gz",
+            "list",
+            "--names",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("compSuccess\ntestSuccess\n"));
+}
+
+#[test]
+fn verify_exercises_of_zip_bundle() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--bundle=exercises.zip", "verify"])
+        .current_dir("tests/fixture/bundle")
+        .assert()
+        .success();
+}
+
+#[test]
+fn list_exercises_of_yaml_bundle() {
+    let dir = std::env::temp_dir().join(format!("rustlings_yaml_bundle_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+        File::create(dir.join("exercises.tgz")).unwrap(),
+        flate2::Compression::default(),
+    ));
+    let files = [
+        (
+            "exercises/info.yaml",
+            "exercises:\n  - name: compSuccess\n    path: compSuccess.rs\n    mode: compile\n    hint: \"\"\n",
+        ),
+        ("exercises/compSuccess.rs", "fn main() {}\n"),
+    ];
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        archive
+            .append_data(&mut header, path, content.as_bytes())
+            .unwrap();
+    }
+    archive.into_inner().unwrap().finish().unwrap();
+
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--bundle", "exercises.tgz", "list", "--names"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("compSuccess\n"));
+}
+
+#[test]
+fn deny_warnings_skips_allowed_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--deny-warnings", "run", "allowed_warnings"])
+        .current_dir("tests/fixture/warnings")
+        .assert()
+        .success();
+}
+
+#[test]
+fn deny_warnings_fails_exercises_with_warnings() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--deny-warnings", "run", "denied_warnings"])
+        .current_dir("tests/fixture/warnings")
+        .assert()
+        .code(1);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "denied_warnings"])
+        .current_dir("tests/fixture/warnings")
+        .assert()
+        .success();
+}
+
+#[test]
+fn myverify_fail_under_threshold() {
+    let output = std::env::temp_dir().join(format!("rustlings_grading_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["myverify", "--fail-under", "40", "--output"])
+        .arg(&output)
+        .current_dir("tests/fixture/grading")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Passed 50.0% of the exercises, meeting the required 40%",
+        ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["myverify", "--fail-under", "80", "--output"])
+        .arg(&output)
+        .current_dir("tests/fixture/grading")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "Passed 50.0% of the exercises, below the required 80%",
+        ));
+    let _ignored = std::fs::remove_file(&output);
+}
+
+#[test]
+fn myverify_renders_report_template() {
+    let dir = std::env::temp_dir().join(format!("rustlings_report_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("report.md.hbs");
+    std::fs::write(
+        &template,
+        "Score: {{statistics.total_succeeds}}/{{statistics.total_exercations}}\n\
+         {{#each exercises}}{{name}}: {{#if result}}ok{{else}}failed{{/if}}\n{{/each}}",
+    )
+    .unwrap();
+    let report = dir.join("report.md");
+    let myverify = |template: &std::path::Path| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["myverify", "--output"])
+            .arg(dir.join("check_result.json"))
+            .arg("--report-template")
+            .arg(template)
+            .arg("--report-out")
+            .arg(&report)
+            .current_dir("tests/fixture/grading")
+            .output()
+            .unwrap()
+    };
+    let rendered = myverify(&template);
+    let written = std::fs::read_to_string(&report).unwrap_or_default();
+    std::fs::write(&template, "{{#each exercises}}").unwrap();
+    let broken = myverify(&template);
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(rendered.status.success());
+    assert_eq!(written, "Score: 1/2\npassing: ok\nfailing: failed\n");
+    assert_eq!(broken.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&broken.stdout).contains("Invalid report template"));
+}
+
+#[test]
+fn myverify_runs_jobs_at_the_same_time() {
+    let dir = std::env::temp_dir().join(format!("rustlings_jobs_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut manifest = String::new();
+    for i in 0..4 {
+        manifest.push_str(&format!(
+            "[[exercises]]\nname = \"slow{i}\"\npath = \"slow{i}.rs\"\nmode = \"compile\"\nhint = \"\"\n\n"
+        ));
+        std::fs::write(
+            dir.join(format!("slow{i}.rs")),
+            "fn main() {\n    std::thread::sleep(std::time::Duration::from_secs(3));\n}\n",
+        )
+        .unwrap();
+    }
+    std::fs::write(dir.join("info.toml"), &manifest).unwrap();
+    std::fs::write(dir.join("check.toml"), &manifest).unwrap();
+
+    let started = std::time::Instant::now();
+    let status = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["myverify", "--jobs", "4", "--output"])
+        .arg(dir.join("check_result.json"))
+        .current_dir(&dir)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    let elapsed = started.elapsed();
+    let _ignored = std::fs::remove_dir_all(&dir);
+
+    assert!(status.success());
+    // One after the other, like on a runner with fewer cores than jobs,
+    // they take 12 seconds
+    assert!(elapsed < Duration::from_secs(9), "took {elapsed:?}");
+}
+
+#[test]
+fn myverify_strict_conflicts_with_no_strict() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["myverify", "--strict", "--no-strict"])
+        .current_dir("tests/fixture/grading")
+        .assert()
+        .code(1)
+        .stdout("--strict and --no-strict can't be given together\n");
+}
+
+#[test]
+fn json_schema_describes_check_results() {
+    let output = std::env::temp_dir().join(format!("rustlings_schema_{}", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["myverify", "--output"])
+        .arg(&output)
+        .current_dir("tests/fixture/grading")
+        .assert()
+        .success();
+    let results: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    let _ignored = std::fs::remove_file(&output);
+
+    let schema = Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("json-schema")
+        .output()
+        .unwrap();
+    assert!(schema.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&schema.stdout).unwrap();
+    let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
+    assert!(schema.is_valid(&results));
+    assert!(!schema.is_valid(&serde_json::json!({ "exercises": [] })));
+}
+
+#[test]
+fn run_under_miri_detects_undefined_behavior() {
+    let miri = Command::new("cargo")
+        .args(["miri", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success());
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "ub", "--miri"])
+        .current_dir("tests/fixture/miri")
+        .assert()
+        .code(1);
+    if miri {
+        assert.stdout(predicates::str::contains(
+            "Miri found undefined behavior in ub.rs",
+        ));
+    } else {
+        assert.stdout(predicates::str::contains(
+            "Miri isn't available for the current toolchain.",
+        ));
+    }
+}